@@ -0,0 +1,40 @@
+// Time of parsing and evaluating every program from codes/ (run with `cargo bench`).
+// It's a plain binary instead of criterion, so it doesn't need any extra dependencies.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use lista7::{interpreter, Config, Interpreter};
+
+const EXAMPLES: [&str; 6] = ["star", "squares", "tree", "fern", "spiral", "race"];
+
+fn main() {
+    let iterations: u32 = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(20);
+
+    println!("{:<10} {:>8} {:>14} {:>14}", "program", "lines", "tree / run", "vm / run");
+    for name in EXAMPLES {
+        let code = fs::read_to_string(format!("codes/{}.txt", name)).unwrap();
+        let blocks = interpreter::parse(&code).unwrap();
+
+        let mut total = Duration::ZERO;
+        let mut total_vm = Duration::ZERO;
+        let mut lines = 0;
+        for _ in 0..iterations {
+            let mut interpreter = Interpreter::new(Config { seed: Some(1), ..Config::default() });
+            let start = Instant::now();
+            interpreter.run_blocks(blocks.clone()).unwrap();
+            total += start.elapsed();
+            lines = interpreter.turtle().lines.len();
+
+            // compilation is counted in the time of the vm
+            let mut interpreter = Interpreter::new(Config { seed: Some(1), ..Config::default() });
+            let start = Instant::now();
+            interpreter.run_compiled(&blocks).unwrap();
+            total_vm += start.elapsed();
+        }
+        println!("{:<10} {:>8} {:>14?} {:>14?}", name, lines, total / iterations, total_vm / iterations);
+    }
+}
@@ -0,0 +1,29 @@
+// rgb values of colors that can be set with setcolor / setcolor pick;
+// svg understands the names directly, other formats need numbers
+pub fn rgb(color: &str) -> (u8, u8, u8) {
+    match color.to_lowercase().as_str() {
+        "black" => (0, 0, 0),
+        "blue" => (0, 0, 255),
+        "green" => (0, 128, 0),
+        "cyan" | "aqua" => (0, 255, 255),
+        "red" => (255, 0, 0),
+        "magenta" => (255, 0, 255),
+        "yellow" => (255, 255, 0),
+        "white" => (255, 255, 255),
+        "brown" => (165, 42, 42),
+        "tan" => (210, 180, 140),
+        "salmon" => (250, 128, 114),
+        "purple" => (128, 0, 128),
+        "orange" => (255, 165, 0),
+        "gray" => (128, 128, 128),
+        "pink" => (255, 192, 203),
+        "violet" => (238, 130, 238),
+        _ => (0, 0, 0), // unknown colors are drawn black
+    }
+}
+
+// the same color as three floats in 0..1, which is what pdf and postscript expect
+pub fn rgb_unit(color: &str) -> (f64, f64, f64) {
+    let (r, g, b) = rgb(color);
+    (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0)
+}
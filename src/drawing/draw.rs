@@ -1,10 +1,31 @@
 use svg::{Document, node::element::Line};
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
 use std::path::Path;
 use super::super::evaluator::turtle::Turtle;
+use super::{dxf, eps, html, json, pdf, term};
 
-pub fn save_image(turtle: Turtle, filename: &str) {
+pub const FORMATS: [&str; 8] = ["svg", "pdf", "eps", "dxf", "html", "json", "term", "term-mono"];
+
+// saves turtle's lines in one of the FORMATS; mm_per_step is used only by dxf
+pub fn save(turtle: Turtle, filename: &str, format: &str, mm_per_step: f64) -> Result<(), String> {
+    let saved = match format {
+        "svg" => save_image(turtle, filename),
+        "pdf" => pdf::save_pdf(turtle, filename),
+        "eps" => eps::save_eps(turtle, filename),
+        "dxf" => dxf::save_dxf(turtle, filename, mm_per_step),
+        "html" => html::save_html(turtle, filename),
+        "json" => json::save_json(turtle, filename),
+        "term" | "term-mono" => {
+            term::print_term(turtle, format == "term");
+            Ok(())
+        }
+        _ => return Err(format!("Unknown format '{}', expected one of: {}", format, FORMATS.join(", "))),
+    };
+    saved.map_err(|e| format!("Can't save images/{}.{}: {}", filename, format, e))
+}
+
+pub fn save_image(turtle: Turtle, filename: &str) -> io::Result<()> {
     // create new document
     let document = Document::new()
         .set("width", turtle.img_x)
@@ -20,13 +41,18 @@ pub fn save_image(turtle: Turtle, filename: &str) {
             .set("x2", line.end_x)
             .set("y2", line.end_y)
             .set("stroke", color)
-            .set("stroke-width", 2);
+            .set("stroke-width", line.width);
         document = document.add(line_element);
     }
 
     // save file
-    let path_str = format!("images/{}.svg", filename);
+    write_output(filename, "svg", document.to_string().as_bytes())
+}
+
+// every format is saved to images/ directory, under the same name as the input file
+pub fn write_output(filename: &str, extension: &str, contents: &[u8]) -> io::Result<()> {
+    let path_str = format!("images/{}.{}", filename, extension);
     let output_path = Path::new(path_str.as_str());
-    let mut file = File::create(output_path).unwrap();
-    file.write_all(document.to_string().as_bytes()).unwrap();
+    let mut file = File::create(output_path)?;
+    file.write_all(contents)
 }
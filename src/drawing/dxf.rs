@@ -0,0 +1,90 @@
+use std::io;
+
+use super::draw::write_output;
+use super::super::evaluator::turtle::Turtle;
+
+// autocad color index closest to the named color (7 is black/white depending on background)
+fn aci(color: &str) -> u8 {
+    match color.to_lowercase().as_str() {
+        "red" => 1,
+        "yellow" => 2,
+        "green" => 3,
+        "cyan" | "aqua" => 4,
+        "blue" => 5,
+        "magenta" => 6,
+        "gray" => 8,
+        "salmon" => 11,
+        "brown" => 16,
+        "orange" => 30,
+        "tan" => 42,
+        "purple" => 200,
+        "violet" => 210,
+        "pink" => 221,
+        _ => 7,
+    }
+}
+
+// every group is a pair of lines: group code and value
+fn group(dxf: &mut String, code: u16, value: &str) {
+    dxf.push_str(&format!("{:>3}\n{}\n", code, value));
+}
+
+// dxf R12 (AC1009) ascii drawing; every line of the turtle is a LINE entity on a layer named after its color;
+// coordinates are in millimetres (mm_per_step millimetres for one turtle step) with y axis pointing up
+pub fn render_dxf(turtle: &Turtle, mm_per_step: f64) -> String {
+    let mut layers: Vec<&str> = Vec::new();
+    for line in &turtle.lines {
+        if !layers.contains(&line.color.as_str()) {
+            layers.push(line.color.as_str());
+        }
+    }
+
+    let mut dxf = String::new();
+
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "HEADER");
+    group(&mut dxf, 9, "$ACADVER");
+    group(&mut dxf, 1, "AC1009");
+    group(&mut dxf, 9, "$EXTMIN");
+    group(&mut dxf, 10, "0.0");
+    group(&mut dxf, 20, "0.0");
+    group(&mut dxf, 9, "$EXTMAX");
+    group(&mut dxf, 10, &format!("{:.3}", turtle.img_x * mm_per_step));
+    group(&mut dxf, 20, &format!("{:.3}", turtle.img_y * mm_per_step));
+    group(&mut dxf, 0, "ENDSEC");
+
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "TABLES");
+    group(&mut dxf, 0, "TABLE");
+    group(&mut dxf, 2, "LAYER");
+    group(&mut dxf, 70, &layers.len().to_string());
+    for layer in &layers {
+        group(&mut dxf, 0, "LAYER");
+        group(&mut dxf, 2, layer);
+        group(&mut dxf, 70, "0");
+        group(&mut dxf, 62, &aci(layer).to_string());
+        group(&mut dxf, 6, "CONTINUOUS");
+    }
+    group(&mut dxf, 0, "ENDTAB");
+    group(&mut dxf, 0, "ENDSEC");
+
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "ENTITIES");
+    for line in &turtle.lines {
+        group(&mut dxf, 0, "LINE");
+        group(&mut dxf, 8, &line.color);
+        group(&mut dxf, 10, &format!("{:.3}", line.start_x * mm_per_step));
+        group(&mut dxf, 20, &format!("{:.3}", (turtle.img_y - line.start_y) * mm_per_step));
+        group(&mut dxf, 30, "0.0");
+        group(&mut dxf, 11, &format!("{:.3}", line.end_x * mm_per_step));
+        group(&mut dxf, 21, &format!("{:.3}", (turtle.img_y - line.end_y) * mm_per_step));
+        group(&mut dxf, 31, "0.0");
+    }
+    group(&mut dxf, 0, "ENDSEC");
+    group(&mut dxf, 0, "EOF");
+    dxf
+}
+
+pub fn save_dxf(turtle: Turtle, filename: &str, mm_per_step: f64) -> io::Result<()> {
+    write_output(filename, "dxf", render_dxf(&turtle, mm_per_step).as_bytes())
+}
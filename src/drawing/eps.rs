@@ -0,0 +1,46 @@
+use std::io;
+
+use super::color::rgb_unit;
+use super::draw::write_output;
+use super::super::evaluator::turtle::Turtle;
+
+// encapsulated postscript, bounding box is the whole turtle's image; y axis is flipped like in pdf
+pub fn render_eps(turtle: &Turtle) -> String {
+    let mut document = String::from("%!PS-Adobe-3.0 EPSF-3.0\n");
+    document.push_str(&format!(
+        "%%BoundingBox: 0 0 {} {}\n",
+        turtle.img_x.ceil() as i64,
+        turtle.img_y.ceil() as i64
+    ));
+    document.push_str(&format!("%%HiResBoundingBox: 0 0 {} {}\n", turtle.img_x, turtle.img_y));
+    document.push_str("%%Creator: lista7\n%%EndComments\n");
+    document.push_str("1 setlinecap 1 setlinejoin\n");
+
+    let mut current_color: Option<&str> = None;
+    let mut current_width: Option<f64> = None;
+    for line in &turtle.lines {
+        if current_width != Some(line.width) {
+            document.push_str(&format!("{} setlinewidth\n", line.width));
+            current_width = Some(line.width);
+        }
+        if current_color != Some(line.color.as_str()) {
+            let (r, g, b) = rgb_unit(&line.color);
+            document.push_str(&format!("{:.3} {:.3} {:.3} setrgbcolor\n", r, g, b));
+            current_color = Some(line.color.as_str());
+        }
+        document.push_str(&format!(
+            "newpath {:.2} {:.2} moveto {:.2} {:.2} lineto stroke\n",
+            line.start_x,
+            turtle.img_y - line.start_y,
+            line.end_x,
+            turtle.img_y - line.end_y
+        ));
+    }
+
+    document.push_str("showpage\n%%EOF\n");
+    document
+}
+
+pub fn save_eps(turtle: Turtle, filename: &str) -> io::Result<()> {
+    write_output(filename, "eps", render_eps(&turtle).as_bytes())
+}
@@ -0,0 +1,126 @@
+use std::io;
+
+use super::draw::write_output;
+use super::json::render_json;
+use super::super::evaluator::turtle::Turtle;
+
+const PLAYER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>__TITLE__</title>
+<style>
+  body { font-family: sans-serif; margin: 16px; }
+  #stage { position: relative; border: 1px solid #ccc; display: inline-block; }
+  #stage canvas { display: block; }
+  #turtles { position: absolute; left: 0; top: 0; }
+  #controls { margin-top: 8px; }
+</style>
+</head>
+<body>
+<div id="stage"><canvas id="lines"></canvas><canvas id="turtles"></canvas></div>
+<div id="controls">
+  <button id="play">Pause</button>
+  <button id="restart">Restart</button>
+  <label>Speed
+    <select id="speed">
+      <option value="0.25">0.25x</option>
+      <option value="0.5">0.5x</option>
+      <option value="1" selected>1x</option>
+      <option value="2">2x</option>
+      <option value="4">4x</option>
+      <option value="16">16x</option>
+    </select>
+  </label>
+  <span id="progress"></span>
+</div>
+<script>
+const drawing = __DRAWING__;  // display list, see src/drawing/json.rs
+const SEGMENTS_PER_FRAME = 2; // at 1x speed
+const TICKS_PER_SECOND = 60;  // wait counts in 1/60 s
+
+const linesCanvas = document.getElementById("lines");
+const turtlesCanvas = document.getElementById("turtles");
+for (const canvas of [linesCanvas, turtlesCanvas]) {
+  canvas.width = drawing.width;
+  canvas.height = drawing.height;
+}
+const ctx = linesCanvas.getContext("2d");
+const overlay = turtlesCanvas.getContext("2d");
+ctx.lineCap = "round";
+
+let next, clock, budget, playing = true, speed = 1, last = null;
+const positions = new Map(); // turtle id -> [x, y] of its last line
+
+function restart() {
+  ctx.clearRect(0, 0, drawing.width, drawing.height);
+  positions.clear();
+  next = 0;
+  clock = 0;
+  budget = 0;
+}
+
+function drawTurtles() {
+  overlay.clearRect(0, 0, drawing.width, drawing.height);
+  overlay.fillStyle = "rgba(0, 128, 0, 0.7)";
+  for (const [x, y] of positions.values()) {
+    overlay.beginPath();
+    overlay.arc(x, y, 5, 0, 2 * Math.PI);
+    overlay.fill();
+  }
+  document.getElementById("progress").textContent = next + " / " + drawing.lines.length;
+}
+
+function frame(timestamp) {
+  const dt = last === null ? 0 : (timestamp - last) / 1000;
+  last = timestamp;
+  if (playing) {
+    budget += SEGMENTS_PER_FRAME * speed;
+    while (next < drawing.lines.length && budget >= 1) {
+      const { x1, y1, x2, y2, color, width, turtle, time } = drawing.lines[next];
+      if (time > clock) {
+        // turtle waits: logical clock runs only until it reaches the next line
+        clock = Math.min(time, clock + dt * TICKS_PER_SECOND * speed);
+        budget = 0;
+        break;
+      }
+      ctx.strokeStyle = color;
+      ctx.lineWidth = width;
+      ctx.beginPath();
+      ctx.moveTo(x1, y1);
+      ctx.lineTo(x2, y2);
+      ctx.stroke();
+      positions.set(turtle, [x2, y2]);
+      next += 1;
+      budget -= 1;
+    }
+    drawTurtles();
+  }
+  requestAnimationFrame(frame);
+}
+
+document.getElementById("play").onclick = (event) => {
+  playing = !playing;
+  event.target.textContent = playing ? "Pause" : "Play";
+};
+document.getElementById("restart").onclick = restart;
+document.getElementById("speed").onchange = (event) => { speed = parseFloat(event.target.value); };
+
+restart();
+requestAnimationFrame(frame);
+</script>
+</body>
+</html>
+"#;
+
+// single html file with recorded display list replayed on canvas - no other files needed to watch it
+pub fn render_html(turtle: &Turtle, title: &str) -> String {
+    let escaped_title = title.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    PLAYER
+        .replace("__TITLE__", &escaped_title)
+        .replace("__DRAWING__", render_json(turtle).trim_end())
+}
+
+pub fn save_html(turtle: Turtle, filename: &str) -> io::Result<()> {
+    write_output(filename, "html", render_html(&turtle, filename).as_bytes())
+}
@@ -0,0 +1,337 @@
+// Display list of the drawing as json, so it can be post-processed by other tools or rendered again
+// by any backend without running the program. Schema (version 1):
+//
+// {
+//   "version": 1,
+//   "width": 800,                 image width
+//   "height": 600,                image height
+//   "lines": [                    lines in the order they were drawn
+//     {
+//       "x1": 400, "y1": 300,     start, in pixels from the top-left corner
+//       "x2": 400, "y2": 200,     end
+//       "color": "red",           color name, as in setcolor
+//       "width": 2,               pen width
+//       "turtle": 0,              id of turtle that drew the line (setturtle)
+//       "time": 0                 time of drawing, in ticks (1/60 s) counted by wait
+//     },
+//     ...
+//   ]
+// }
+//
+// When reading, "version" and the fields of a line after "color" are optional (defaults: 1, 2, 0, 0).
+
+use std::{fs, io};
+
+use super::draw::write_output;
+use super::super::evaluator::turtle::{Line, Turtle};
+
+pub const VERSION: u32 = 1;
+
+// arrays and objects nested deeper are rejected, so the recursive reader can't overflow the stack
+const MAX_DEPTH: usize = 128;
+
+// json has no NaN / infinity, such values are written as 0
+fn json_number(value: f64) -> String {
+    if value.is_finite() { format!("{}", value) } else { "0".to_string() }
+}
+
+pub fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            // "</script>" inside of the string would end the script when embedded in html
+            '<' => escaped.push_str("\\u003c"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+pub fn render_json(turtle: &Turtle) -> String {
+    let lines: Vec<String> = turtle.lines.iter().map(|line| {
+        format!(
+            "{{\"x1\":{},\"y1\":{},\"x2\":{},\"y2\":{},\"color\":{},\"width\":{},\"turtle\":{},\"time\":{}}}",
+            json_number(line.start_x),
+            json_number(line.start_y),
+            json_number(line.end_x),
+            json_number(line.end_y),
+            json_string(&line.color),
+            json_number(line.width),
+            line.turtle,
+            json_number(line.time)
+        )
+    }).collect();
+    format!(
+        "{{\"version\":{},\"width\":{},\"height\":{},\"lines\":[\n{}\n]}}\n",
+        VERSION,
+        json_number(turtle.img_x),
+        json_number(turtle.img_y),
+        lines.join(",\n")
+    )
+}
+
+pub fn save_json(turtle: Turtle, filename: &str) -> io::Result<()> {
+    write_output(filename, "json", render_json(&turtle).as_bytes())
+}
+
+#[derive(Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+// small recursive descent parser, enough for the display list (and any other valid json)
+struct Reader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    depth: usize, // arrays and objects the reader is in
+}
+
+impl Reader<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        Err(format!("json: {} at byte {}", message, self.pos))
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(&format!("expected '{}'", c as char))
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        if self.input[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            self.error("unexpected character")
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.input.get(self.pos) {
+            None => self.error("unexpected end of input"),
+            Some(b'{') | Some(b'[') => self.nested(),
+            Some(b'"') => Ok(Json::Str(self.string()?)),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(_) => self.number(),
+        }
+    }
+
+    fn nested(&mut self) -> Result<Json, String> {
+        if self.depth == MAX_DEPTH {
+            return self.error(&format!("nested deeper than {} levels", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let value = if self.input[self.pos] == b'{' { self.object() } else { self.array() };
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect(b'{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.input.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return self.error("expected ',' or '}'"),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.input.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return self.error("expected ',' or ']'"),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.input.get(self.pos) {
+                None => return self.error("unterminated string"),
+                Some(b'"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    let escaped = self.input.get(self.pos + 1).copied();
+                    self.pos += 2;
+                    let c = match escaped {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return self.error("invalid escape"),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(&c) => {
+                    bytes.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        String::from_utf8(bytes).or_else(|_| self.error("invalid utf-8"))
+    }
+
+    // 4 hex digits after \u
+    fn hex4(&mut self) -> Option<u32> {
+        let hex = self.input.get(self.pos..self.pos + 4)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u32::from_str_radix(h, 16).ok());
+        self.pos += 4;
+        hex
+    }
+
+    // \uXXXX, characters outside of the basic plane are written as surrogate pair: \ud83d\ude00
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let code = match self.hex4() {
+            Some(high @ 0xd800..=0xdbff) => {
+                if !self.input[self.pos..].starts_with(b"\\u") {
+                    return self.error("unpaired surrogate in unicode escape");
+                }
+                self.pos += 2;
+                match self.hex4() {
+                    Some(low @ 0xdc00..=0xdfff) => 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00),
+                    _ => return self.error("unpaired surrogate in unicode escape"),
+                }
+            }
+            Some(code) => code,
+            None => return self.error("invalid unicode escape"),
+        };
+        char::from_u32(code).map_or_else(|| self.error("invalid unicode escape"), Ok)
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        while self.pos < self.input.len() && matches!(self.input[self.pos], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.pos]).ok()
+            .and_then(|s| s.parse().ok())
+            .map(Json::Number)
+            .map_or_else(|| self.error("invalid number"), Ok)
+    }
+}
+
+fn field<'a>(fields: &'a [(String, Json)], name: &str) -> Option<&'a Json> {
+    fields.iter().find(|(key, _)| key == name).map(|(_, value)| value)
+}
+
+fn number_field(fields: &[(String, Json)], name: &str, default: Option<f64>) -> Result<f64, String> {
+    match (field(fields, name), default) {
+        (Some(Json::Number(n)), _) => Ok(*n),
+        (None, Some(d)) => Ok(d),
+        (None, None) => Err(format!("json: missing field \"{}\"", name)),
+        _ => Err(format!("json: field \"{}\" is not a number", name)),
+    }
+}
+
+// reads display list back into turtle (with the lines history only), so it can be saved by any backend
+pub fn parse_json(input: &str) -> Result<Turtle, String> {
+    let mut reader = Reader { input: input.as_bytes(), pos: 0, depth: 0 };
+    let document = reader.value()?;
+    reader.skip_whitespace();
+    if reader.pos != reader.input.len() {
+        return reader.error("trailing characters");
+    }
+
+    let fields = match document {
+        Json::Object(fields) => fields,
+        _ => return Err("json: display list has to be an object".to_string()),
+    };
+    let version = number_field(&fields, "version", Some(VERSION as f64))?;
+    if version != VERSION as f64 {
+        return Err(format!("json: unsupported version {}", version));
+    }
+
+    let mut turtle = Turtle::new(number_field(&fields, "width", None)?, number_field(&fields, "height", None)?);
+    let lines = match field(&fields, "lines") {
+        Some(Json::Array(lines)) => lines,
+        _ => return Err("json: missing array \"lines\"".to_string()),
+    };
+    for line in lines {
+        let line = match line {
+            Json::Object(line) => line,
+            _ => return Err("json: line has to be an object".to_string()),
+        };
+        let color = match field(line, "color") {
+            Some(Json::Str(color)) => color.clone(),
+            _ => return Err("json: line without \"color\"".to_string()),
+        };
+        turtle.lines.push(Line {
+            start_x: number_field(line, "x1", None)?,
+            start_y: number_field(line, "y1", None)?,
+            end_x: number_field(line, "x2", None)?,
+            end_y: number_field(line, "y2", None)?,
+            color,
+            width: number_field(line, "width", Some(2.0))?,
+            turtle: number_field(line, "turtle", Some(0.0))? as u32,
+            time: number_field(line, "time", Some(0.0))?,
+        });
+    }
+    Ok(turtle)
+}
+
+pub fn load_json(path: &str) -> Result<Turtle, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Error reading file {}: {}", path, e))?;
+    parse_json(&content)
+}
@@ -1 +1,8 @@
-pub mod draw;
\ No newline at end of file
+pub mod draw;
+pub mod color;
+pub mod pdf;
+pub mod eps;
+pub mod term;
+pub mod dxf;
+pub mod html;
+pub mod json;
@@ -0,0 +1,69 @@
+use std::io;
+
+use super::color::rgb_unit;
+use super::draw::write_output;
+use super::super::evaluator::turtle::Turtle;
+
+// content stream with all lines; pdf has y axis pointing up, so y is flipped
+fn content_stream(turtle: &Turtle) -> String {
+    let mut content = String::from("1 J 1 j\n");
+    let mut current_color: Option<&str> = None;
+    let mut current_width: Option<f64> = None;
+    for line in &turtle.lines {
+        if current_width != Some(line.width) {
+            content.push_str(&format!("{} w\n", line.width));
+            current_width = Some(line.width);
+        }
+        if current_color != Some(line.color.as_str()) {
+            let (r, g, b) = rgb_unit(&line.color);
+            content.push_str(&format!("{:.3} {:.3} {:.3} RG\n", r, g, b));
+            current_color = Some(line.color.as_str());
+        }
+        content.push_str(&format!(
+            "{:.2} {:.2} m {:.2} {:.2} l S\n",
+            line.start_x,
+            turtle.img_y - line.start_y,
+            line.end_x,
+            turtle.img_y - line.end_y
+        ));
+    }
+    content
+}
+
+// single page pdf document, page has the same size (in points) as the turtle's image
+pub fn render_pdf(turtle: &Turtle) -> String {
+    let content = content_stream(turtle);
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R /Resources << >> >>",
+            turtle.img_x, turtle.img_y
+        ),
+        format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content),
+    ];
+
+    let mut document = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(document.len());
+        document.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+
+    // cross-reference table, every entry has to be exactly 20 bytes long
+    let xref_offset = document.len();
+    document.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for offset in offsets {
+        document.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    document.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+    document
+}
+
+pub fn save_pdf(turtle: Turtle, filename: &str) -> io::Result<()> {
+    write_output(filename, "pdf", render_pdf(&turtle).as_bytes())
+}
@@ -0,0 +1,116 @@
+use std::env;
+
+use super::color::rgb;
+use super::super::evaluator::turtle::Turtle;
+
+// every terminal cell is one braille character with 2x4 dots
+const DOTS_X: usize = 2;
+const DOTS_Y: usize = 4;
+
+// bit of braille dot at (x, y) inside a cell, see unicode block U+2800
+fn dot_bit(x: usize, y: usize) -> u32 {
+    match (x, y) {
+        (0, 3) => 0x40,
+        (1, 3) => 0x80,
+        (0, _) => 1 << y,
+        _ => 1 << (y + 3),
+    }
+}
+
+// size of the terminal asked from the tty; stdout can be redirected, so stderr and stdin are tried too
+#[cfg(unix)]
+fn tty_size() -> Option<(usize, usize)> {
+    for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO] {
+        let mut size = libc::winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+        // safety: TIOCGWINSZ only writes to the winsize struct given to it
+        let result = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) };
+        if result == 0 && size.ws_col > 1 && size.ws_row > 1 {
+            return Some((size.ws_col as usize, size.ws_row as usize));
+        }
+    }
+    None
+}
+
+#[cfg(not(unix))]
+fn tty_size() -> Option<(usize, usize)> {
+    None
+}
+
+// size of terminal from the tty, or from COLUMNS / LINES variables when it's not a terminal (e.g. on CI),
+// 80x24 if they are not set either; one row is left for the prompt
+pub fn terminal_size() -> (usize, usize) {
+    let read = |name: &str, default: usize| {
+        env::var(name).ok().and_then(|v| v.parse().ok()).filter(|&v| v > 1).unwrap_or(default)
+    };
+    let (cols, rows) = tty_size().unwrap_or_else(|| (read("COLUMNS", 80), read("LINES", 24)));
+    (cols, rows - 1)
+}
+
+// rasterizes turtle's lines into cols x rows braille characters, keeping aspect ratio of the image
+pub fn render_term(turtle: &Turtle, cols: usize, rows: usize, colored: bool) -> String {
+    let width = cols * DOTS_X;
+    let height = rows * DOTS_Y;
+    let scale = (width as f64 / turtle.img_x).min(height as f64 / turtle.img_y);
+
+    let mut cells = vec![0u32; cols * rows];
+    let mut colors: Vec<Option<&str>> = vec![None; cols * rows];
+
+    for line in &turtle.lines {
+        let mut plot = |x: i64, y: i64| {
+            if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+                return;
+            }
+            let (x, y) = (x as usize, y as usize);
+            let cell = (y / DOTS_Y) * cols + x / DOTS_X;
+            cells[cell] |= dot_bit(x % DOTS_X, y % DOTS_Y);
+            colors[cell] = Some(line.color.as_str());
+        };
+
+        let (x0, y0) = ((line.start_x * scale) as i64, (line.start_y * scale) as i64);
+        let (x1, y1) = ((line.end_x * scale) as i64, (line.end_y * scale) as i64);
+
+        // bresenham's line algorithm
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+        let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+        loop {
+            plot(x, y);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    let mut output = String::new();
+    for row in 0..rows {
+        let mut last_color: Option<&str> = None;
+        for col in 0..cols {
+            let cell = row * cols + col;
+            if colored && colors[cell].is_some() && colors[cell] != last_color {
+                let (r, g, b) = rgb(colors[cell].unwrap());
+                output.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+                last_color = colors[cell];
+            }
+            output.push(char::from_u32(0x2800 + cells[cell]).unwrap());
+        }
+        if colored && last_color.is_some() {
+            output.push_str("\x1b[0m");
+        }
+        output.push('\n');
+    }
+    output
+}
+
+pub fn print_term(turtle: Turtle, colored: bool) {
+    let (cols, rows) = terminal_size();
+    print!("{}", render_term(&turtle, cols, rows, colored));
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::evaluator::value::Value;
+
+// everything that can go wrong while running logo program
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogoError {
+    Io(String),                // program file can't be read
+    Parse(String),             // program is not valid logo
+    UnknownVariable(String),   // :name is not set
+    UnknownProcedure(String),  // call of procedure, that isn't defined
+    DuplicateProcedure(String), // procedure defined more than once in one program
+    WrongArity { name: String, expected: usize, got: usize },
+    NoOutput(String),          // procedure used as a value, but it doesn't return any
+    NotACommand(String),       // reporter used as an instruction, its value would be lost
+    NotInProcedure(String),    // output used outside of a procedure
+    Native { name: String, message: String }, // error returned by primitive registered from rust
+    BadInput { name: String, value: Value }, // e.g. random -1, first []
+    StepLimit(u64),            // limits from Config, see evaluator/limits
+    DepthLimit(usize),
+    LineLimit(usize),
+    TimeLimit(Duration),
+    Unsupported(String),       // feature that the bytecode compiler can't handle (yet)
+}
+
+impl fmt::Display for LogoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogoError::Io(e) => write!(f, "{}", e),
+            LogoError::Parse(e) => write!(f, "Parse error: {}", e),
+            LogoError::UnknownVariable(name) => write!(f, "Value {} not in environment", name),
+            LogoError::UnknownProcedure(name) => write!(f, "Function '{}' not found in environment", name),
+            LogoError::DuplicateProcedure(name) => write!(f, "Function '{}' is defined more than once", name),
+            LogoError::WrongArity { name, expected, got } => write!(
+                f, "{} expects {} argument(s), but got {}", name, expected, got
+            ),
+            LogoError::NoOutput(name) => write!(f, "{} didn't output a value", name),
+            LogoError::NotACommand(name) => write!(f, "You don't say what to do with {}", name),
+            LogoError::NotInProcedure(name) => write!(f, "Can only use {} inside a procedure", name),
+            LogoError::Native { name, message } => write!(f, "{}: {}", name, message),
+            LogoError::BadInput { name, value } => write!(f, "{} doesn't like {} as input", name, value.show()),
+            LogoError::StepLimit(max) => write!(f, "Program exceeded limit of {} steps", max),
+            LogoError::DepthLimit(max) => write!(f, "Procedure calls nested deeper than {} levels", max),
+            LogoError::LineLimit(max) => write!(f, "Drawing exceeded limit of {} lines", max),
+            LogoError::TimeLimit(max) => write!(f, "Program exceeded time limit of {:?}", max),
+            LogoError::Unsupported(what) => write!(f, "{} is not supported by the compiler", what),
+        }
+    }
+}
+
+impl std::error::Error for LogoError {}
@@ -0,0 +1,318 @@
+// Compiles AST to flat bytecode, run by the stack machine in evaluator/vm.
+// Names are resolved once here: every variable gets a slot with a stack of values (like in Env),
+// every procedure name gets a slot in the procedure table, and control flow becomes jumps,
+// so the machine doesn't look anything up by string or clone any blocks while running.
+
+use crate::error::LogoError;
+use crate::parser::block::{Block, CmpOp, Condition};
+use crate::parser::command::Command;
+use crate::parser::unit::{ArithOp, Unit};
+use super::definitions::hoist;
+use super::environment::{Env, Primitive};
+use super::lists::is_list_primitive;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    // values, on the stack of numbers
+    Push(f64),
+    Load(usize), // variable slot
+    RepCount,
+    Arith(ArithOp),
+    Compare(CmpOp), // pushes 1 (true) or 0 (false)
+    Random,
+    RandomRange,
+    RandomFloat,
+    Report(usize, usize), // procedure slot, number of arguments <- (name args) used as a value, also user procedure
+
+    // turtle commands, take their argument from the stack
+    Forward,
+    Back,
+    Left,
+    Right,
+    Wait,
+    SetTurtle,
+    ClearScreen,
+    PenUp,
+    PenDown,
+    SetColor(usize),     // index in Program::colors
+    SetColorPick(usize), // index in Program::picks
+    Rerandom(bool),      // true when seed is on the stack
+    Print,               // number and new line (show of a number is the same)
+    Type,                // number without new line
+
+    // control
+    Step, // counts one evaluated block, for limits
+    Jump(usize),
+    JumpIfFalse(usize),
+    LoopStart,       // takes number of iterations from the stack
+    LoopNext(usize), // next iteration, or end of loop and jump to the address
+    Define(usize),   // index in Program::definitions
+    Call(usize, usize), // procedure slot, number of arguments
+    TailCall(usize, usize), // call at the end of procedure, reuses its frame
+    Stop,            // returns from procedure, or ends the program
+    Output,          // returns from procedure called as reporter, with the value from the stack
+    Return,          // end of procedure body
+    Halt,            // end of the program
+}
+
+// to ... end, with its body compiled at `entry`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub slot: usize,
+    pub params: Vec<usize>, // variable slots
+    pub entry: usize,
+    pub block: Block, // source, passed to Env when defined, so the tree-walking evaluator knows it too
+}
+
+// name from the procedure table, with the primitive of that name registered from rust (if any)
+#[derive(Debug, Clone)]
+pub struct ProcName {
+    pub name: String,
+    pub primitive: Option<Primitive>,
+    pub defined: Option<usize>, // definition from earlier runs, already known to Env
+}
+
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub code: Vec<Op>,
+    pub vars: Vec<String>,
+    pub procs: Vec<ProcName>,
+    pub definitions: Vec<Definition>,
+    pub colors: Vec<String>,
+    pub picks: Vec<Vec<String>>,
+}
+
+struct Compiler<'a> {
+    env: &'a Env,
+    program: Program,
+    bodies: Vec<(usize, Vec<Block>)>, // procedure bodies waiting to be compiled after the main code
+}
+
+impl Compiler<'_> {
+    fn var_slot(&mut self, name: &str) -> usize {
+        match self.program.vars.iter().position(|var| var == name) {
+            Some(slot) => slot,
+            None => {
+                self.program.vars.push(name.to_string());
+                self.program.vars.len() - 1
+            }
+        }
+    }
+
+    // procedure names are case-insensitive, like in Env
+    fn proc_slot(&mut self, name: &str) -> usize {
+        let name = name.to_lowercase();
+        match self.program.procs.iter().position(|proc| proc.name == name) {
+            Some(slot) => slot,
+            None => {
+                self.program.procs.push(ProcName {
+                    primitive: self.env.get_primitive(&name).cloned(),
+                    name,
+                    defined: None,
+                });
+                self.program.procs.len() - 1
+            }
+        }
+    }
+
+    fn emit(&mut self, op: Op) -> usize {
+        self.program.code.push(op);
+        self.program.code.len() - 1
+    }
+
+    // address of the next instruction
+    fn here(&self) -> usize {
+        self.program.code.len()
+    }
+
+    fn unit(&mut self, unit: &Unit) -> Result<(), LogoError> {
+        match unit {
+            Unit::Val(n) => { self.emit(Op::Push(*n)); },
+            Unit::Var(name) => {
+                let slot = self.var_slot(name);
+                self.emit(Op::Load(slot));
+            },
+            Unit::RepCount => { self.emit(Op::RepCount); },
+            Unit::Exp(l, o, r) => {
+                self.unit(l)?;
+                self.unit(r)?;
+                self.emit(Op::Arith(*o));
+            },
+            Unit::Random(bound) => {
+                self.unit(bound)?;
+                self.emit(Op::Random);
+            },
+            Unit::RandomRange(low, high) => {
+                self.unit(low)?;
+                self.unit(high)?;
+                self.emit(Op::RandomRange);
+            },
+            Unit::RandomFloat(bound) => {
+                self.unit(bound)?;
+                self.emit(Op::RandomFloat);
+            },
+            // the machine has only numbers on its stack
+            Unit::Word(_) | Unit::List(_) => return Err(LogoError::Unsupported("words and lists".to_string())),
+            Unit::Call(name, _) if is_list_primitive(name) => return Err(LogoError::Unsupported(name.clone())),
+            Unit::Call(name, args) => {
+                for arg in args {
+                    self.unit(arg)?;
+                }
+                let slot = self.proc_slot(name);
+                self.emit(Op::Report(slot, args.len()));
+            },
+        }
+        Ok(())
+    }
+
+    fn condition(&mut self, cond: &Condition) -> Result<(), LogoError> {
+        self.unit(&cond.left)?;
+        self.unit(&cond.right)?;
+        self.emit(Op::Compare(cond.operator));
+        Ok(())
+    }
+
+    fn command(&mut self, command: &Command) -> Result<(), LogoError> {
+        let op = match command {
+            Command::Forward(unit) => { self.unit(unit)?; Op::Forward },
+            Command::Back(unit) => { self.unit(unit)?; Op::Back },
+            Command::Left(unit) => { self.unit(unit)?; Op::Left },
+            Command::Right(unit) => { self.unit(unit)?; Op::Right },
+            Command::Wait(unit) => { self.unit(unit)?; Op::Wait },
+            Command::Print(unit) | Command::Show(unit) => { self.unit(unit)?; Op::Print },
+            Command::Type(unit) => { self.unit(unit)?; Op::Type },
+            Command::SetTurtle(unit) => { self.unit(unit)?; Op::SetTurtle },
+            Command::ClearScreen() => Op::ClearScreen,
+            Command::PenUp() => Op::PenUp,
+            Command::PenDown() => Op::PenDown,
+            Command::SetColor(Unit::Word(color)) => {
+                self.program.colors.push(color.clone());
+                Op::SetColor(self.program.colors.len() - 1)
+            },
+            Command::SetColor(_) => return Err(LogoError::Unsupported("setcolor with computed color".to_string())),
+            Command::SetColorPick(colors) => {
+                self.program.picks.push(colors.clone());
+                Op::SetColorPick(self.program.picks.len() - 1)
+            },
+            Command::Rerandom(seed) => {
+                if let Some(unit) = seed {
+                    self.unit(unit)?;
+                }
+                Op::Rerandom(seed.is_some())
+            },
+            Command::Stop() => Op::Stop,
+            Command::Output(unit) => { self.unit(unit)?; Op::Output },
+            _ => return Ok(()), // ignored commands, like in the evaluator
+        };
+        self.emit(op);
+        Ok(())
+    }
+
+    fn block(&mut self, block: &Block) -> Result<(), LogoError> {
+        self.emit(Op::Step);
+        match block {
+            Block::Single(c) => self.command(c)?,
+            Block::If(c, instructions) => {
+                self.condition(c)?;
+                let jump = self.emit(Op::JumpIfFalse(0));
+                self.blocks(instructions)?;
+                self.program.code[jump] = Op::JumpIfFalse(self.here());
+            },
+            Block::Repeat(u, instructions) => {
+                self.unit(u)?;
+                self.emit(Op::LoopStart);
+                let next = self.emit(Op::LoopNext(0));
+                self.blocks(instructions)?;
+                self.emit(Op::Jump(next));
+                self.program.code[next] = Op::LoopNext(self.here());
+            },
+            Block::Function(name, params, instructions) => {
+                let slot = self.proc_slot(name);
+                let params = params.iter().map(|param| self.var_slot(param)).collect();
+                self.program.definitions.push(Definition { slot, params, entry: 0, block: block.clone() });
+                let index = self.program.definitions.len() - 1;
+                self.bodies.push((index, instructions.clone()));
+                self.emit(Op::Define(index));
+            },
+            Block::Call(name, args) => {
+                for arg in args {
+                    self.unit(arg)?;
+                }
+                let slot = self.proc_slot(name);
+                self.emit(Op::Call(slot, args.len()));
+            },
+        }
+        Ok(())
+    }
+
+    fn blocks(&mut self, blocks: &[Block]) -> Result<(), LogoError> {
+        for block in blocks {
+            self.block(block)?;
+        }
+        Ok(())
+    }
+
+    // end of procedure body: call there (also at the end of the last if) is a tail call
+    fn tail_blocks(&mut self, blocks: &[Block]) -> Result<(), LogoError> {
+        let (last, rest) = match blocks.split_last() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+        self.blocks(rest)?;
+        match last {
+            Block::Call(name, args) => {
+                self.emit(Op::Step);
+                for arg in args {
+                    self.unit(arg)?;
+                }
+                let slot = self.proc_slot(name);
+                self.emit(Op::TailCall(slot, args.len()));
+            },
+            Block::If(c, instructions) => {
+                self.emit(Op::Step);
+                self.condition(c)?;
+                let jump = self.emit(Op::JumpIfFalse(0));
+                self.tail_blocks(instructions)?;
+                self.program.code[jump] = Op::JumpIfFalse(self.here());
+            },
+            block => self.block(block)?,
+        }
+        Ok(())
+    }
+
+    // bodies are compiled after the main code; they can contain definitions too, so it's a work list
+    fn bodies(&mut self) -> Result<(), LogoError> {
+        while let Some((index, instructions)) = self.bodies.pop() {
+            self.program.definitions[index].entry = self.here();
+            self.tail_blocks(&instructions)?;
+            self.emit(Op::Return);
+        }
+        Ok(())
+    }
+}
+
+// procedures defined in Env by earlier runs are compiled too, so the program can call them
+pub fn compile(blocks: &[Block], env: &Env) -> Result<Program, LogoError> {
+    let blocks = hoist(blocks.to_vec(), env)?;
+    let mut compiler = Compiler {
+        env,
+        program: Program { code: Vec::new(), vars: Vec::new(), procs: Vec::new(), definitions: Vec::new(), colors: Vec::new(), picks: Vec::new() },
+        bodies: Vec::new(),
+    };
+
+    compiler.blocks(&blocks)?;
+    compiler.emit(Op::Halt);
+
+    for function in env.functions() {
+        if let Block::Function(name, params, instructions) = &**function {
+            let slot = compiler.proc_slot(name);
+            let params = params.iter().map(|param| compiler.var_slot(param)).collect();
+            compiler.program.definitions.push(Definition { slot, params, entry: 0, block: (**function).clone() });
+            let index = compiler.program.definitions.len() - 1;
+            compiler.program.procs[slot].defined = Some(index);
+            compiler.bodies.push((index, instructions.clone()));
+        }
+    }
+    compiler.bodies()?;
+    Ok(compiler.program)
+}
@@ -0,0 +1,105 @@
+// Pass over the whole program before it runs: definitions (to ... end) are moved to its beginning, also the ones
+// written inside of repeats, ifs and other procedures, so procedures can be called above their definition, and calls
+// of procedures, that are not defined anywhere, are found before anything is drawn. Procedures can't be defined
+// twice in one program.
+
+use std::collections::HashSet;
+
+use crate::error::LogoError;
+use crate::parser::block::Block;
+use crate::parser::unit::Unit;
+use super::environment::Env;
+use super::eval::RUN_PRIMITIVES;
+use super::lists::is_list_primitive;
+use super::optimize::units;
+use super::templates::is_higher_order;
+
+// names of procedures defined in the program, also inside other procedures
+fn collect_definitions(blocks: &[Block], defined: &mut HashSet<String>) -> Result<(), LogoError> {
+    for block in blocks {
+        if let Block::Function(name, _, _) = block {
+            if !defined.insert(name.to_lowercase()) {
+                return Err(LogoError::DuplicateProcedure(name.clone()));
+            }
+        }
+        if let Block::Repeat(_, body) | Block::If(_, body) | Block::Function(_, _, body) = block {
+            collect_definitions(body, defined)?;
+        }
+    }
+    Ok(())
+}
+
+// procedures defined in earlier runs and primitives are known too;
+// names in lists (run [...], apply "name) are checked only when they run; case doesn't matter in names
+fn check_name(name: &str, defined: &HashSet<String>, env: &Env) -> Result<(), LogoError> {
+    let lowercase = name.to_lowercase();
+    let known = defined.contains(&lowercase) || env.get_fun(name).is_ok() || env.get_primitive(name).is_some()
+        || is_list_primitive(&lowercase) || is_higher_order(&lowercase) || RUN_PRIMITIVES.contains(&lowercase.as_str());
+    if known {
+        Ok(())
+    } else {
+        Err(LogoError::UnknownProcedure(name.to_string()))
+    }
+}
+
+fn check_unit(unit: &Unit, defined: &HashSet<String>, env: &Env) -> Result<(), LogoError> {
+    match unit {
+        Unit::Call(name, args) => {
+            check_name(name, defined, env)?;
+            args.iter().try_for_each(|arg| check_unit(arg, defined, env))
+        }
+        Unit::Exp(l, _, r) | Unit::RandomRange(l, r) => {
+            check_unit(l, defined, env)?;
+            check_unit(r, defined, env)
+        }
+        Unit::Random(u) | Unit::RandomFloat(u) => check_unit(u, defined, env),
+        _ => Ok(()),
+    }
+}
+
+fn check_calls(blocks: &[Block], defined: &HashSet<String>, env: &Env) -> Result<(), LogoError> {
+    for block in blocks {
+        if let Block::Call(name, _) = block {
+            check_name(name, defined, env)?;
+        }
+        for unit in units(block) {
+            check_unit(unit, defined, env)?;
+        }
+        if let Block::Repeat(_, body) | Block::If(_, body) | Block::Function(_, _, body) = block {
+            check_calls(body, defined, env)?;
+        }
+    }
+    Ok(())
+}
+
+// blocks without definitions, which are moved to `definitions` (with definitions in their bodies moved out too);
+// so every procedure, that the call check knows about, is defined before the program runs
+fn take_definitions(blocks: Vec<Block>, definitions: &mut Vec<Block>) -> Vec<Block> {
+    let mut rest = Vec::new();
+    for block in blocks {
+        match block {
+            Block::Function(name, params, body) => {
+                let mut nested = Vec::new();
+                let body = take_definitions(body, &mut nested);
+                definitions.push(Block::Function(name, params, body));
+                definitions.append(&mut nested);
+            }
+            Block::Repeat(times, body) => rest.push(Block::Repeat(times, take_definitions(body, definitions))),
+            Block::If(condition, body) => rest.push(Block::If(condition, take_definitions(body, definitions))),
+            block => rest.push(block),
+        }
+    }
+    rest
+}
+
+// program with all definitions first (in the order they were written), the rest keeps its order
+pub fn hoist(blocks: Vec<Block>, env: &Env) -> Result<Vec<Block>, LogoError> {
+    let mut defined = HashSet::new();
+    collect_definitions(&blocks, &mut defined)?;
+    check_calls(&blocks, &defined, env)?;
+
+    let mut hoisted = Vec::new();
+    let rest = take_definitions(blocks, &mut hoisted);
+    hoisted.extend(rest);
+    Ok(hoisted)
+}
@@ -1,62 +1,239 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use super::super::parser::block::Block;
+use super::limits::{Limits, Usage};
+use super::output::Output;
+use super::turtle::Turtle;
+use super::value::Value;
+use crate::error::LogoError;
+
+pub type NativeProcedure = Rc<dyn Fn(&mut Turtle, &[f64]) -> Result<(), String>>;
+pub type NativeReporter = Rc<dyn Fn(&[f64]) -> Result<f64, String>>;
+
+// procedure written in rust by the program embedding the interpreter, with its number of arguments
+#[derive(Clone)]
+pub enum Primitive {
+    Procedure(usize, NativeProcedure), // command, e.g. stamplogo
+    Reporter(usize, NativeReporter),   // returns value, e.g. (sensor 1)
+}
+
+impl Primitive {
+    pub fn arity(&self) -> usize {
+        match self {
+            Primitive::Procedure(arity, _) | Primitive::Reporter(arity, _) => *arity,
+        }
+    }
+}
+
+impl fmt::Debug for Primitive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Primitive::Procedure(arity, _) => write!(f, "Procedure({})", arity),
+            Primitive::Reporter(arity, _) => write!(f, "Reporter({})", arity),
+        }
+    }
+}
+
+// scope of single procedure call: names of variables bound by it (its parameters),
+// which are removed when the call ends
+#[derive(Debug)]
+struct Frame {
+    bound: Vec<String>,
+}
 
-// wrapper for local environment inside block
-#[derive(Debug, PartialEq, Clone)]
+// Environment of running program. Logo has dynamic scope, so every variable name has a stack of its values with
+// the innermost binding on top (lookup is single hash map access), and every procedure call / repeat has its frame
+// on a stack, that knows what has to be removed when it ends. Env is passed by reference everywhere, it's never copied.
+#[derive(Debug)]
 pub struct Env {
-    vars: HashMap<String, Vec<f64>>,
-    functions: HashMap<String, Block>
+    vars: HashMap<String, Vec<Value>>,
+    frames: Vec<Frame>,   // procedure scopes
+    loops: Vec<u64>,      // loop scopes: repcount of every running repeat, innermost last
+    functions: HashMap<String, Rc<Block>>,
+    primitives: HashMap<String, Primitive>,
+    pub limits: Limits,
+    pub usage: Usage,
+    pub output: Output, // text of print, show and type
+    seed: u64,
+    rng: RefCell<ChaCha8Rng>, // in RefCell, so random numbers can be drawn while evaluating with &Env
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Env {
+    // seeded from entropy, use with_seed for reproducible drawings
     pub fn new() -> Self {
+        Self::with_seed(rand::thread_rng().gen())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
         Env {
             vars: HashMap::new(),
+            frames: Vec::new(),
+            loops: Vec::new(),
             functions: HashMap::new(),
+            primitives: HashMap::new(),
+            limits: Limits::default(),
+            usage: Usage::default(),
+            output: Output::default(),
+            seed,
+            rng: RefCell::new(ChaCha8Rng::seed_from_u64(seed)),
         }
     }
 
-    pub fn get_var(&self, var_name: &str) -> Result<f64, String>  {
-        match self.vars.get(var_name) {
-            Some(stack) => Ok(*stack.last().ok_or_else(|| format!("Value {} not in environment", var_name))?),
-            None => Err(format!("Value {} not in environment", var_name))
+    pub fn get_var(&self, var_name: &str) -> Result<Value, LogoError>  {
+        match self.vars.get(var_name).and_then(|stack| stack.last()) {
+            Some(value) => Ok(value.clone()),
+            None => Err(LogoError::UnknownVariable(var_name.to_string()))
         }
     }
 
-    pub fn set_var(&mut self, var_name: String, val: f64) {
-        self.vars.entry(var_name).or_insert_with(Vec::new).push(val);
+    // like logo's make: changes the innermost binding of the variable, or creates global one
+    pub fn set_var(&mut self, var_name: String, val: Value) {
+        let stack = self.vars.entry(var_name).or_default();
+        match stack.last_mut() {
+            Some(value) => *value = val,
+            None => stack.push(val),
+        }
     }
 
-    pub fn update_many_vars(&mut self, params: Vec<String>, args: Vec<f64>) -> Result<(), String> {
+    // new procedure scope with parameters bound to arguments
+    pub fn push_frame(&mut self, name: &str, params: &[String], args: Vec<Value>) -> Result<(), LogoError> {
         if params.len() != args.len() {
-            return Err("Number of parameters and arguments do not match".to_string());
+            return Err(LogoError::WrongArity { name: name.to_string(), expected: params.len(), got: args.len() });
         }
-
-        for (param, arg) in params.into_iter().zip(args.into_iter()) {
-            self.set_var(param, arg);
+        for (param, arg) in params.iter().zip(args) {
+            self.vars.entry(param.clone()).or_default().push(arg);
         }
+        self.frames.push(Frame { bound: params.to_vec() });
+        Ok(())
+    }
 
+    // tail call: called procedure runs in the scope of the caller (which has nothing more to do), so caller's
+    // variables stay visible like with normal call; parameter already bound by the scope gets new value,
+    // so tail recursion doesn't make the scope grow
+    pub fn tail_frame(&mut self, name: &str, params: &[String], args: Vec<Value>) -> Result<(), LogoError> {
+        if params.len() != args.len() {
+            return Err(LogoError::WrongArity { name: name.to_string(), expected: params.len(), got: args.len() });
+        }
+        let frame = match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => return self.push_frame(name, params, args),
+        };
+        for (param, arg) in params.iter().zip(args) {
+            let stack = self.vars.entry(param.clone()).or_default();
+            if frame.bound.contains(param) {
+                if let Some(value) = stack.last_mut() {
+                    *value = arg;
+                }
+            } else {
+                stack.push(arg);
+                frame.bound.push(param.clone());
+            }
+        }
         Ok(())
     }
 
-    pub fn pop_var(&mut self, var_name: &str) -> Option<f64> {
-        self.vars.get_mut(var_name).and_then(|stack| stack.pop())
+    // removes the innermost procedure scope with all its variables
+    pub fn pop_frame(&mut self) {
+        if let Some(frame) = self.frames.pop() {
+            for name in frame.bound {
+                if let Some(stack) = self.vars.get_mut(&name) {
+                    stack.pop();
+                }
+            }
+        }
     }
 
-    pub fn pop_many_vars(&mut self, params: Vec<String>) -> Vec<Option<f64>> {
-        params.into_iter().map(|key| self.pop_var(&key)).collect()
+    // number of active procedure calls
+    pub fn depth(&self) -> usize {
+        self.frames.len()
     }
 
-    pub fn get_fun(&self, fun_name: &str) -> Result<Block, String> {
-        match self.functions.get(fun_name) {
+    pub fn push_loop(&mut self) {
+        self.loops.push(0);
+    }
+
+    pub fn next_iteration(&mut self) {
+        if let Some(count) = self.loops.last_mut() {
+            *count += 1;
+        }
+    }
+
+    pub fn pop_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    // iteration of the innermost repeat (from 1), -1 outside of repeat like in UCBLogo
+    pub fn repcount(&self) -> f64 {
+        self.loops.last().map_or(-1.0, |count| *count as f64)
+    }
+
+    // names of procedures and primitives are case-insensitive, like keywords: Square and square are the same
+    pub fn get_fun(&self, fun_name: &str) -> Result<Rc<Block>, LogoError> {
+        match self.functions.get(&fun_name.to_lowercase()) {
             Some(value) => Ok(value.clone()),
-            None => Err(format!("Function '{}' not found in environment", fun_name)),
+            None => Err(LogoError::UnknownProcedure(fun_name.to_string())),
         }
     }
 
+    // user procedures defined so far
+    pub fn functions(&self) -> impl Iterator<Item = &Rc<Block>> {
+        self.functions.values()
+    }
+
     pub fn set_fun(&mut self, fun_name: String, block: Block) {
-        self.functions.insert(fun_name, block);
+        self.functions.insert(fun_name.to_lowercase(), Rc::new(block));
     }
 
-}
\ No newline at end of file
+    // primitives are looked up only when there is no user procedure with the same name
+    pub fn get_primitive(&self, name: &str) -> Option<&Primitive> {
+        self.primitives.get(&name.to_lowercase())
+    }
+
+    pub fn primitives(&self) -> impl Iterator<Item = (&String, &Primitive)> {
+        self.primitives.iter()
+    }
+
+    pub fn set_primitive(&mut self, name: String, primitive: Primitive) {
+        self.primitives.insert(name.to_lowercase(), primitive);
+    }
+
+    // forgets variables and user procedures, primitives are kept and random numbers start over
+    pub fn clear(&mut self) {
+        self.vars.clear();
+        self.frames.clear();
+        self.loops.clear();
+        self.functions.clear();
+        self.reseed(self.seed);
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.reseed(seed);
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // starts random numbers over from given seed (rerandom), without changing the env's own seed;
+    // chacha gives the same numbers for the same seed on every platform
+    pub fn reseed(&self, seed: u64) {
+        *self.rng.borrow_mut() = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    pub fn rng(&self) -> std::cell::RefMut<'_, ChaCha8Rng> {
+        self.rng.borrow_mut()
+    }
+}
@@ -1,165 +1,527 @@
-use crate::parser::block::Condition;
-use super::super::parser::unit::Unit;
+use crate::parser::block::{parse_condition, CmpOp, Condition};
+use super::super::parser::unit::{parse_unit, Unit};
+use super::super::parser::program::{parse_with, Arities};
 use super::super::parser::command::Command;
 use super::super::parser::block::Block;
-use super::turtle::Turtle;
-use super::environment::Env;
-use rand::{seq::SliceRandom, thread_rng, Rng};
-
-
-pub fn eval_unit(unit: Unit, env: Env) -> f64 {
-    match unit {
-        Unit::Val(n) => n,
-        Unit::Var(s) => 
-            match env.get_var(&s) {
-                Ok(val) => val, 
-                Err(e) => {
-                    eprintln!("Error: {}", e);
-                    0.0  
-                }
-            },
+use super::turtle::{Direction, Pen, Turn, Turtle};
+use super::definitions::hoist;
+use super::environment::{Env, Primitive};
+use super::lists::{call_list_primitive, default_arity, is_list_primitive, LIST_PRIMITIVES};
+use super::templates::{call_higher_order, call_template, foreach, is_higher_order, run_template};
+use super::value::Value;
+use crate::error::LogoError;
+use rand::{seq::SliceRandom, Rng};
+use std::rc::Rc;
+
+
+// reporters and commands, that run lists
+pub(crate) const RUN_PRIMITIVES: [&str; 5] = ["run", "runresult", "apply", "invoke", "foreach"];
+
+// numbers of inputs of all procedures known in env (built-in, primitives and defined ones),
+// so code parsed while running can call them without parentheses
+pub fn arities(env: &Env) -> Arities {
+    let mut arities = builtin_arities();
+    for (name, primitive) in env.primitives() {
+        arities.insert(name.clone(), primitive.arity());
+    }
+    for function in env.functions() {
+        if let Block::Function(name, params, _) = &**function {
+            arities.insert(name.to_lowercase(), params.len());
+        }
+    }
+    arities
+}
+
+// numbers of inputs of the built-in procedures, that every program can call without parentheses
+pub fn builtin_arities() -> Arities {
+    let mut arities = Arities::new();
+    for name in LIST_PRIMITIVES {
+        arities.extend(default_arity(name).map(|arity| (name.to_string(), arity)));
+    }
+    for (name, arity) in [("run", 1), ("runresult", 1), ("apply", 2), ("invoke", 2), ("map", 2), ("filter", 2), ("reduce", 2), ("foreach", 2)] {
+        arities.insert(name.to_string(), arity);
+    }
+    arities
+}
+
+// how evaluation of instructions ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Flow {
+    Next,          // go on with the next instruction
+    Stop,          // stop: leave the procedure (or the whole program)
+    Output(Value), // output: leave the procedure with the value
+}
+
+// turtle is needed, because units can call user procedures, which can draw
+pub fn eval_unit(unit: &Unit, turtle: &mut Turtle, env: &mut Env) -> Result<Value, LogoError> {
+    let value = match unit {
+        Unit::Val(n) => Value::Number(*n),
+        Unit::Var(s) => env.get_var(s)?,
+        Unit::RepCount => Value::Number(env.repcount()),
         Unit::Exp(l, o, r) => {
-            let l_val = eval_unit(*l, env.clone());
-            let r_val = eval_unit(*r, env.clone());
-            match o.as_str() {
-                "+" => l_val + r_val,
-                "-" => l_val - r_val,
-                "*" => l_val * r_val,
-                "/" => l_val / r_val,
-                _ => {
-                    eprintln!("Unknown operator: {}", o);
-                    0.0
-                }
-            }
+            let l = eval_number(l, turtle, env, o.name())?;
+            Value::Number(o.apply(l, eval_number(r, turtle, env, o.name())?))
         },
-        Unit::Random(bound) => {
-            let bound_val = eval_unit(*bound, env.clone()) as u32;
-            let mut rng = rand::thread_rng();
-            let random_number: u32 = rng.gen_range(1..bound_val);
-            random_number as f64
+        Unit::Random(bound) => Value::Number(random(eval_number(bound, turtle, env, "random")?, env)?),
+        Unit::RandomRange(low, high) => {
+            let low = eval_number(low, turtle, env, "random")?;
+            Value::Number(random_range(low, eval_number(high, turtle, env, "random")?, env)?)
         },
-        _ => 0.0 // not evaluated Units
+        Unit::RandomFloat(bound) => Value::Number(random_float(eval_number(bound, turtle, env, "randomfloat")?, env)?),
+        Unit::Word(w) => Value::Word(w.clone()),
+        Unit::List(items) => Value::List(eval_args(items, turtle, env)?),
+        Unit::Call(name, args) => {
+            let args = eval_args(args, turtle, env)?;
+            call_reporter(name, args, turtle, env)?
+        },
+    };
+    Ok(value)
+}
+
+// reporter called by name with evaluated inputs
+pub(crate) fn call_reporter(name: &str, args: Vec<Value>, turtle: &mut Turtle, env: &mut Env) -> Result<Value, LogoError> {
+    if let Some(result) = call_list_primitive(name, &args, env) {
+        return result;
+    }
+    if let Some(result) = call_higher_order(name, &args, turtle, env) {
+        return result;
+    }
+    match name {
+        // (run [expression]) gives its value, (runresult [expression]) list with it, or empty list for []
+        "run" | "runresult" => {
+            check_arity(name, 1, args.len())?;
+            let value = run_expression(name, &args[0], turtle, env)?;
+            return match (name, value) {
+                ("run", Some(value)) => Ok(value),
+                ("run", None) => Err(LogoError::NoOutput(name.to_string())),
+                (_, value) => Ok(Value::List(value.into_iter().collect())),
+            };
+        }
+        "apply" | "invoke" => {
+            let (procedure, inputs) = called(name, &args)?;
+            return call_template(name, &procedure, inputs, turtle, env);
+        }
+        _ => {}
+    }
+    match env.get_primitive(name) {
+        Some(Primitive::Reporter(arity, f)) => {
+            check_arity(name, *arity, args.len())?;
+            let numbers = numbers(name, &args)?;
+            Ok(Value::Number(f(&numbers).map_err(|message| LogoError::Native { name: name.to_string(), message })?))
+        }
+        Some(Primitive::Procedure(..)) => Err(LogoError::NoOutput(name.to_string())),
+        None => {
+            let procedure = env.get_fun(name)?;
+            call_procedure(name.to_string(), procedure, args, Call::Reporter, turtle, env)?
+                .ok_or_else(|| LogoError::NoOutput(name.to_string()))
+        }
+    }
+}
+
+// called procedure (name or template) and its inputs: apply "name [inputs], invoke "name inputs...
+fn called(name: &str, args: &[Value]) -> Result<(Value, Vec<Value>), LogoError> {
+    match (name, args) {
+        ("apply", [procedure, inputs]) => Ok((procedure.clone(), inputs.as_list(name)?.to_vec())),
+        ("apply", _) => Err(LogoError::WrongArity { name: name.to_string(), expected: 2, got: args.len() }),
+        (_, [procedure, inputs @ ..]) => Ok((procedure.clone(), inputs.to_vec())),
+        (_, []) => Err(LogoError::WrongArity { name: name.to_string(), expected: 1, got: 0 }),
+    }
+}
+
+// lists given to run are written back as text and parsed, so [repeat 4 [fd :size rt 90]] runs like the same code would
+pub(crate) fn run_expression(name: &str, list: &Value, turtle: &mut Turtle, env: &mut Env) -> Result<Option<Value>, LogoError> {
+    if list.as_list(name)?.is_empty() {
+        return Ok(None);
+    }
+    let code = list.source();
+    let arities = arities(env);
+    if let Ok((rest, unit)) = parse_unit(&code, &arities) {
+        if rest.trim().is_empty() {
+            return Ok(Some(eval_unit(&unit, turtle, env)?));
+        }
+    }
+    // comparison, eg template of filter [? > 10]
+    match parse_condition(&code, &arities) {
+        Ok((rest, cond)) if rest.trim().is_empty() => Ok(Some(Value::Bool(eval_condition(&cond, turtle, env)?))),
+        _ => Err(LogoError::Parse(format!("{} can't evaluate {}", name, list.show()))),
+    }
+}
+
+pub(crate) fn run_instructions(name: &str, list: &Value, turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    list.as_list(name)?;
+    let instructions = parse_with(&list.source(), &arities(env))?;
+    eval_instructions(&instructions, turtle, env)
+}
+
+// unit that has to be a number, `name` is the primitive that takes it (for the error message)
+pub fn eval_number(unit: &Unit, turtle: &mut Turtle, env: &mut Env, name: &str) -> Result<f64, LogoError> {
+    eval_unit(unit, turtle, env)?.as_number(name)
+}
+
+// primitives registered from rust take only numbers
+fn numbers(name: &str, args: &[Value]) -> Result<Vec<f64>, LogoError> {
+    args.iter().map(|arg| arg.as_number(name)).collect()
+}
+
+// random works on integers that f64 can represent exactly
+fn is_integer(value: f64) -> bool {
+    value.is_finite() && value.fract() == 0.0 && value.abs() < 9007199254740992.0
+}
+
+// `random n`: integer from 0 to n - 1
+pub(crate) fn random(bound: f64, env: &Env) -> Result<f64, LogoError> {
+    if !is_integer(bound) || bound < 1.0 {
+        return Err(LogoError::BadInput { name: "random".to_string(), value: Value::Number(bound) });
+    }
+    Ok(env.rng().gen_range(0..bound as i64) as f64)
+}
+
+// `(random lo hi)`: integer from lo to hi, both included
+pub(crate) fn random_range(low: f64, high: f64, env: &Env) -> Result<f64, LogoError> {
+    for value in [low, high] {
+        if !is_integer(value) {
+            return Err(LogoError::BadInput { name: "random".to_string(), value: Value::Number(value) });
+        }
+    }
+    if high < low {
+        return Err(LogoError::BadInput { name: "random".to_string(), value: Value::Number(high) });
+    }
+    Ok(env.rng().gen_range(low as i64..=high as i64) as f64)
+}
+
+// `randomfloat n`: from [0, n)
+pub(crate) fn random_float(bound: f64, env: &Env) -> Result<f64, LogoError> {
+    if !bound.is_finite() || bound <= 0.0 {
+        return Err(LogoError::BadInput { name: "randomfloat".to_string(), value: Value::Number(bound) });
+    }
+    Ok(env.rng().gen_range(0.0..bound))
+}
+
+// `setturtle n`: turtles are numbered from 0
+pub(crate) fn turtle_id(id: f64) -> Result<u32, LogoError> {
+    if !is_integer(id) || id < 0.0 || id > u32::MAX as f64 {
+        return Err(LogoError::BadInput { name: "setturtle".to_string(), value: Value::Number(id) });
+    }
+    Ok(id as u32)
+}
+
+// `rerandom n`: seed is a whole number from 0, so different seeds always give different numbers
+pub(crate) fn rerandom_seed(value: f64) -> Result<u64, LogoError> {
+    if !is_integer(value) || value < 0.0 {
+        return Err(LogoError::BadInput { name: "rerandom".to_string(), value: Value::Number(value) });
     }
+    Ok(value as u64)
 }
 
-pub fn eval_command(command: Command, turtle: &mut Turtle, env: Env) -> Option<()>  {
+fn eval_args(args: &[Unit], turtle: &mut Turtle, env: &mut Env) -> Result<Vec<Value>, LogoError> {
+    args.iter().map(|arg| eval_unit(arg, turtle, env)).collect()
+}
+
+pub(crate) fn check_arity(name: &str, expected: usize, got: usize) -> Result<(), LogoError> {
+    if expected != got {
+        return Err(LogoError::WrongArity { name: name.to_string(), expected, got });
+    }
+    Ok(())
+}
+
+pub fn eval_command(command: &Command, turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError>  {
     match command {
         Command::Left(unit) => {
-            turtle.rotate("left", eval_unit(unit, env));
+            let angle = eval_number(unit, turtle, env, "left")?;
+            turtle.rotate(Turn::Left, angle);
         }
         Command::Right(unit) => {
-            turtle.rotate("right", eval_unit(unit, env));
+            let angle = eval_number(unit, turtle, env, "right")?;
+            turtle.rotate(Turn::Right, angle);
         }
         Command::Forward(unit) => {
-            turtle.go("forward", eval_unit(unit, env));
+            let distance = eval_number(unit, turtle, env, "forward")?;
+            turtle.go(Direction::Forward, distance);
         }
         Command::Back(unit) => {
-            turtle.go("back", eval_unit(unit, env));
+            let distance = eval_number(unit, turtle, env, "back")?;
+            turtle.go(Direction::Back, distance);
         }
         Command::ClearScreen() => {
             turtle.clear_screen();
         }
         Command::PenUp() => {
-            turtle.move_pen("up");
+            turtle.move_pen(Pen::Up);
         }
         Command::PenDown() => {
-            turtle.move_pen("down");
+            turtle.move_pen(Pen::Down);
         },
-        Command::SetColor(color) => {
+        Command::SetColor(unit) => {
+            let color = eval_unit(unit, turtle, env)?.as_word("setcolor")?;
             turtle.change_color(color);
         },
         Command::SetColorPick(colors) => {
-            let mut rng = thread_rng();
-            if let Some(random_color) = colors.choose(&mut rng) {
-                turtle.change_color(random_color.to_string());
-            } else {
-                eprintln!("Empty vector");
+            match colors.choose(&mut *env.rng()) {
+                Some(random_color) => turtle.change_color(random_color.to_string()),
+                None => return Err(LogoError::BadInput { name: "pick".to_string(), value: Value::List(vec![]) }),
             }
         }
-        Command::Stop() => {return None},
+        Command::Wait(unit) => {
+            let ticks = eval_number(unit, turtle, env, "wait")?;
+            turtle.wait(ticks);
+        }
+        Command::SetTurtle(unit) => {
+            let id = eval_number(unit, turtle, env, "setturtle")?;
+            turtle.set_turtle(turtle_id(id)?);
+        }
+        Command::Rerandom(seed) => {
+            let seed = match seed {
+                Some(unit) => rerandom_seed(eval_number(unit, turtle, env, "rerandom")?)?,
+                None => env.seed(),
+            };
+            env.reseed(seed);
+        }
+        Command::Print(unit) => {
+            let text = format!("{}\n", eval_unit(unit, turtle, env)?);
+            env.output.write(&text);
+        }
+        Command::Show(unit) => {
+            let text = format!("{}\n", eval_unit(unit, turtle, env)?.show());
+            env.output.write(&text);
+        }
+        Command::Type(unit) => {
+            let text = eval_unit(unit, turtle, env)?.to_string();
+            env.output.write(&text);
+        }
+        Command::Stop() => return Ok(Flow::Stop),
+        Command::Output(unit) => return Ok(Flow::Output(eval_unit(unit, turtle, env)?)),
         _ => {} // some commands are ignored, but it is desired behaviour (e.g. showturtle and window doesn't change my image)
     }
-    Some(())
+    Ok(Flow::Next)
 }
 
 
-fn eval_condition(cond: Condition, env: Env) -> bool {
-    let l_val = eval_unit(cond.left, env.clone());
-    let r_val = eval_unit(cond.right, env.clone());
-    match cond.operator.as_str() {
-        "<" => l_val < r_val,
-        "==" => l_val == r_val,
-        ">" => l_val > r_val,
-        _ => {
-            eprintln!("Unknown operator: {}", cond.operator);
-            false
+fn eval_condition(cond: &Condition, turtle: &mut Turtle, env: &mut Env) -> Result<bool, LogoError> {
+    let (left, right) = (eval_unit(&cond.left, turtle, env)?, eval_unit(&cond.right, turtle, env)?);
+    match cond.operator {
+        // words and lists can be compared only for equality
+        CmpOp::Equal => Ok(left.equals(&right)),
+        o => Ok(o.apply(left.as_number(o.name())?, right.as_number(o.name())?)),
+    }
+}
+
+// evaluates instructions one by one, until the end, stop or output
+fn eval_instructions(instructions: &[Block], turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    for instruction in instructions {
+        match eval_block(instruction, turtle, env)? {
+            Flow::Next => {}
+            flow => return Ok(flow),
         }
     }
+    Ok(Flow::Next)
 }
 
-pub fn eval_block(block: &Block, turtle: &mut Turtle, env: &mut Env) -> Option<()> {
-    match block {
-        Block::Single(c) => {
-            if eval_command(c.clone(), turtle, env.clone()).is_none() {
-                return None; 
+// how user procedure was called: as an instruction, or in an expression, where it has to output a value
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Call {
+    Command,
+    Reporter,
+}
+
+// user procedure called as the last thing done by other procedure, with evaluated arguments
+struct TailCall {
+    name: String,
+    procedure: Rc<Block>,
+    args: Vec<Value>,
+}
+
+// how body of a procedure ended
+enum Body {
+    Done(Flow),
+    Tail(TailCall),
+}
+
+// like eval_instructions, but call of user procedure at the very end (also at the end of the last if) is not
+// evaluated, it's returned instead, so the caller can be removed from the stack before it runs
+fn eval_body(instructions: &[Block], turtle: &mut Turtle, env: &mut Env) -> Result<Body, LogoError> {
+    let (last, rest) = match instructions.split_last() {
+        Some(split) => split,
+        None => return Ok(Body::Done(Flow::Next)),
+    };
+    match eval_instructions(rest, turtle, env)? {
+        Flow::Next => {}
+        flow => return Ok(Body::Done(flow)),
+    }
+    match last {
+        Block::Call(name, args) => {
+            if let Ok(procedure) = env.get_fun(name) {
+                env.usage.step(&env.limits, turtle.lines.len())?;
+                let args = eval_args(args, turtle, env)?;
+                return Ok(Body::Tail(TailCall { name: name.clone(), procedure, args }));
             }
-        },
+        }
+        Block::If(c, instructions) => {
+            env.usage.step(&env.limits, turtle.lines.len())?;
+            if eval_condition(c, turtle, env)? {
+                return eval_body(instructions, turtle, env);
+            }
+            return Ok(Body::Done(Flow::Next));
+        }
+        _ => {}
+    }
+    Ok(Body::Done(eval_block(last, turtle, env)?))
+}
+
+// every nested call uses rust stack (about 12 KB in debug build, 2 KB in release), so when less than STACK_RED_ZONE
+// is left, the call continues on a new STACK_SEGMENT allocated on the heap; deep recursion is limited by
+// Limits::max_depth only, not by the size of the thread's stack
+const STACK_RED_ZONE: usize = 256 * 1024;
+const STACK_SEGMENT: usize = 4 * 1024 * 1024;
+
+// every call has its own frame in Env, which is removed however the procedure ends (also by error);
+// stop and output end only this procedure, output gives the value of the call (Ok(Some)) when it was called as reporter
+fn call_procedure(name: String, procedure: Rc<Block>, args: Vec<Value>, call: Call, turtle: &mut Turtle, env: &mut Env) -> Result<Option<Value>, LogoError> {
+    env.usage.check_depth(&env.limits, env.depth())?;
+    env.push_frame(&name, &[], Vec::new())?; // empty scope, the first call binds its parameters like the tail ones
+    let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+        run_procedure(TailCall { name, procedure, args }, call, turtle, env)
+    });
+    env.pop_frame();
+    result
+}
+
+// tail calls are done in a loop in the same scope, without nesting, so tail-recursive procedures (like spirals)
+// can go on for any number of calls, without hitting the depth limit or overflowing the stack
+fn run_procedure(mut tail: TailCall, call: Call, turtle: &mut Turtle, env: &mut Env) -> Result<Option<Value>, LogoError> {
+    loop {
+        let instructions = match &*tail.procedure {
+            Block::Function(_, params, instructions) => {
+                env.tail_frame(&tail.name, params, tail.args)?;
+                instructions
+            }
+            _ => return Ok(None),
+        };
+        let flow = match eval_body(instructions, turtle, env)? {
+            // value of a reporter can't come from a command called at its end, so this call is not a tail one
+            Body::Tail(next) if call == Call::Reporter => {
+                call_procedure(next.name, next.procedure, next.args, Call::Command, turtle, env)?;
+                Flow::Next
+            }
+            Body::Tail(next) => {
+                tail = next;
+                continue;
+            }
+            Body::Done(flow) => flow,
+        };
+        return match (flow, call) {
+            (Flow::Output(value), Call::Reporter) => Ok(Some(value)),
+            (Flow::Output(_), Call::Command) => Err(LogoError::NotACommand(tail.name)),
+            (_, Call::Reporter) => Err(LogoError::NoOutput(tail.name)),
+            (_, Call::Command) => Ok(None),
+        };
+    }
+}
+
+fn eval_repeat(n: f64, instructions: &[Block], turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    let mut i = 0.0;
+    while i < n {
+        if i > 0.0 {
+            env.usage.step(&env.limits, turtle.lines.len())?; // loops with empty effect count too
+        }
+        env.next_iteration();
+        match eval_instructions(instructions, turtle, env)? {
+            Flow::Next => {}
+            flow => return Ok(flow),
+        }
+        i += 1.0;
+    }
+    Ok(Flow::Next)
+}
+
+// stop and output are passed up to the procedure (and they leave all loops on the way)
+pub fn eval_block(block: &Block, turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    env.usage.step(&env.limits, turtle.lines.len())?;
+    match block {
+        Block::Single(c) => eval_command(c, turtle, env),
         Block::If(c, instructions) => {
-            if eval_condition(c.clone(), env.clone()) {
-                for instruction in instructions {
-                    if eval_block(instruction, turtle, env).is_none() {
-                        return None; 
-                    }
-                }
+            if eval_condition(c, turtle, env)? {
+                return eval_instructions(instructions, turtle, env);
             }
+            Ok(Flow::Next)
         },
         Block::Repeat(u, instructions) => {
-            let n = eval_unit(u.clone(), env.clone());
-            let mut i = 0.0;
-            while i < n {
-                for instruction in instructions {
-                    if eval_block(instruction, turtle, env).is_none() {
-                        return None; 
-                    }
-                }
-                i += 1.0;
-            }
+            let n = eval_number(u, turtle, env, "repeat")?;
+            // loop scope is removed however the loop ends
+            env.push_loop();
+            let result = eval_repeat(n, instructions, turtle, env);
+            env.pop_loop();
+            result
         },
         Block::Function(name, _, _) => {
             env.set_fun(name.to_string(), block.clone());
+            Ok(Flow::Next)
         },
         Block::Call(name, args) => {
-            let f = env.get_fun(name); 
-                let evaluated_args: Vec<f64> = args.iter()
-                .map(|arg| eval_unit(arg.clone(), env.clone())) 
-                .collect();
+            let evaluated_args = eval_args(args, turtle, env)?;
             // println!("{} called with args: {:#?}", name, evaluated_args); // debug
-            match f.unwrap() {
-                Block::Function(_, params, instructions) => {
-                    match env.update_many_vars(params.clone(), evaluated_args) {
-                        Ok(_) => {
-                            for instruction in &instructions {
-                                if eval_block(instruction, turtle, env).is_none() {
-                                    break;
-                                }
-                            }
-                            env.pop_many_vars(params);
-                        }
-                        Err(_) => { eprintln!("Wrong number of arguments") }
-                    }
-                },
-                _ => { eprintln!("Not a function") }
+            call_command(name, evaluated_args, turtle, env)
+        },
+    }
+}
+
+// procedure called by name with evaluated inputs, as an instruction; stop or output is passed up from `run`
+fn call_command(name: &str, args: Vec<Value>, turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    if let Ok(procedure) = env.get_fun(name) {
+        call_procedure(name.to_string(), procedure, args, Call::Command, turtle, env)?;
+        return Ok(Flow::Next);
+    }
+    // no user procedure, so it has to be primitive
+    match env.get_primitive(name).cloned() {
+        Some(Primitive::Procedure(arity, f)) => {
+            check_arity(name, arity, args.len())?;
+            f(turtle, &numbers(name, &args)?).map_err(|message| LogoError::Native { name: name.to_string(), message })?;
+            Ok(Flow::Next)
+        }
+        Some(Primitive::Reporter(..)) => Err(LogoError::NotACommand(name.to_string())),
+        None => match name {
+            // instructions run in the current scope, so stop or output in them ends the procedure that runs them
+            "run" => {
+                check_arity(name, 1, args.len())?;
+                run_instructions(name, &args[0], turtle, env)
             }
+            "apply" | "invoke" => {
+                let (procedure, inputs) = called(name, &args)?;
+                run_template(name, &procedure, inputs, turtle, env)
+            }
+            "foreach" => foreach(&args, turtle, env),
+            "runresult" => Err(LogoError::NotACommand(name.to_string())),
+            _ if is_list_primitive(name) || is_higher_order(name) => Err(LogoError::NotACommand(name.to_string())),
+            _ => Err(LogoError::UnknownProcedure(name.to_string())),
         },
     }
-    Some(()) 
+}
+
+// apply and invoke can call built-in commands too (apply "fd [10]): they are written as an instruction and run
+pub(crate) fn apply_command(name: &str, inputs: Vec<Value>, turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    let known = env.get_fun(name).is_ok() || env.get_primitive(name).is_some() || is_list_primitive(name)
+        || is_higher_order(name) || RUN_PRIMITIVES.contains(&name);
+    if known {
+        return call_command(name, inputs, turtle, env);
+    }
+    let mut instruction = vec![Value::Word(name.to_string())];
+    instruction.extend(inputs.iter().map(Value::quoted));
+    match parse_with(&Value::List(instruction).source(), &arities(env)) {
+        Ok(blocks) if matches!(blocks.as_slice(), [Block::Single(_)]) => eval_instructions(&blocks, turtle, env),
+        _ => Err(LogoError::UnknownProcedure(name.to_string())),
+    }
 }
 
 
-pub fn eval(blocks: Vec<Block>, turtle: &mut Turtle, env: &mut Env) {
+// stop ends the whole program, output can be used only in procedures;
+// definitions are hoisted first (evaluator/definitions), so procedures can be called before their `to`
+pub fn eval(blocks: Vec<Block>, turtle: &mut Turtle, env: &mut Env) -> Result<(), LogoError> {
+    let blocks = hoist(blocks, env)?;
     for block in blocks {
-        if eval_block(&block, turtle, env).is_none() {
-            break;
+        match eval_block(&block, turtle, env)? {
+            Flow::Next => {}
+            Flow::Stop => break,
+            Flow::Output(_) => return Err(LogoError::NotInProcedure("output".to_string())),
         }
     }
+    Ok(())
 }
@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use crate::error::LogoError;
+
+// limits for programs that can't be trusted (e.g. `repeat 1e12` or recursion without base case);
+// None means no limit; by default only the depth is limited, so endless loops and tail recursion
+// (to f f end) need max_steps or max_time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+    pub max_steps: Option<u64>,     // evaluated blocks (every instruction and every iteration of repeat)
+    pub max_depth: Option<usize>,   // nested procedure calls (tail calls are not nested)
+    pub max_lines: Option<usize>,   // lines in the drawing
+    pub max_time: Option<Duration>, // wall-clock time of single run
+}
+
+// the stack grows as needed (see call_procedure in evaluator/eval), so the default depth only stops nested recursion
+// without base case before it takes all memory; ordinary recursion never gets close to it
+pub const DEFAULT_MAX_DEPTH: usize = 10_000;
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits { max_steps: None, max_depth: Some(DEFAULT_MAX_DEPTH), max_lines: None, max_time: None }
+    }
+}
+
+impl Limits {
+    pub fn unlimited() -> Self {
+        Limits { max_steps: None, max_depth: None, max_lines: None, max_time: None }
+    }
+}
+
+// how much of the limits was used in the current run
+#[derive(Debug, Clone)]
+pub struct Usage {
+    pub steps: u64,
+    pub started: Instant,
+}
+
+impl Default for Usage {
+    fn default() -> Self {
+        Usage { steps: 0, started: Instant::now() }
+    }
+}
+
+impl Usage {
+    // called for every evaluated block
+    pub fn step(&mut self, limits: &Limits, lines: usize) -> Result<(), LogoError> {
+        self.steps += 1;
+        if let Some(max) = limits.max_steps {
+            if self.steps > max {
+                return Err(LogoError::StepLimit(max));
+            }
+        }
+        if let Some(max) = limits.max_lines {
+            if lines > max {
+                return Err(LogoError::LineLimit(max));
+            }
+        }
+        // reading clock is the slowest part, so it's checked only every 1024 steps
+        if let Some(max) = limits.max_time {
+            if self.steps.is_multiple_of(1024) && self.started.elapsed() > max {
+                return Err(LogoError::TimeLimit(max));
+            }
+        }
+        Ok(())
+    }
+
+    // called before every procedure call, with number of calls that are already running
+    pub fn check_depth(&self, limits: &Limits, depth: usize) -> Result<(), LogoError> {
+        if let Some(max) = limits.max_depth {
+            if depth >= max {
+                return Err(LogoError::DepthLimit(max));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+// Built-in reporters working on lists (and on words, which are lists of characters in logo):
+// first, butfirst (bf), last, butlast (bl), item, count, fput, lput, list, sentence (se), emptyp, memberp, pick.
+// They are called like other reporters: first :points, or in parentheses with any number of inputs: (list 1 2 3).
+
+use rand::Rng;
+
+use crate::error::LogoError;
+use super::environment::Env;
+use super::eval::check_arity;
+use super::value::Value;
+
+// number of inputs, None for primitives taking any number of them
+fn arity(name: &str) -> Option<Option<usize>> {
+    match name {
+        "first" | "butfirst" | "bf" | "last" | "butlast" | "bl" | "count" | "emptyp" | "pick" => Some(Some(1)),
+        "item" | "fput" | "lput" | "memberp" => Some(Some(2)),
+        "list" | "sentence" | "se" => Some(None),
+        _ => None,
+    }
+}
+
+pub fn is_list_primitive(name: &str) -> bool {
+    arity(name).is_some()
+}
+
+pub const LIST_PRIMITIVES: [&str; 16] = [
+    "first", "butfirst", "bf", "last", "butlast", "bl", "item", "count", "fput", "lput", "list", "sentence", "se",
+    "emptyp", "memberp", "pick",
+];
+
+// number of inputs taken without parentheses: list and sentence take two, like in UCBLogo
+pub fn default_arity(name: &str) -> Option<usize> {
+    arity(name).map(|arity| arity.unwrap_or(2))
+}
+
+fn bad_input(name: &str, value: &Value) -> LogoError {
+    LogoError::BadInput { name: name.to_string(), value: value.clone() }
+}
+
+fn chars(word: &str) -> Vec<Value> {
+    word.chars().map(|c| Value::Word(c.to_string())).collect()
+}
+
+// items of a list, or characters of a word
+fn items(name: &str, value: &Value) -> Result<Vec<Value>, LogoError> {
+    match value {
+        Value::List(items) => Ok(items.clone()),
+        value => Ok(chars(&value.as_word(name)?)),
+    }
+}
+
+// list of the same kind as `like`: list, or word made of the characters
+fn rebuild(like: &Value, items: Vec<Value>) -> Value {
+    match like {
+        Value::List(_) => Value::List(items),
+        _ => Value::Word(items.iter().map(|item| item.to_string()).collect()),
+    }
+}
+
+fn non_empty(name: &str, value: &Value) -> Result<Vec<Value>, LogoError> {
+    let items = items(name, value)?;
+    if items.is_empty() {
+        return Err(bad_input(name, value));
+    }
+    Ok(items)
+}
+
+// None when `name` is not a list primitive
+pub fn call_list_primitive(name: &str, args: &[Value], env: &Env) -> Option<Result<Value, LogoError>> {
+    let arity = arity(name)?;
+    if let Some(expected) = arity {
+        if let Err(e) = check_arity(name, expected, args.len()) {
+            return Some(Err(e));
+        }
+    }
+    Some(list_primitive(name, args, env))
+}
+
+fn list_primitive(name: &str, args: &[Value], env: &Env) -> Result<Value, LogoError> {
+    let value = match name {
+        "first" => non_empty(name, &args[0])?.swap_remove(0),
+        "last" => non_empty(name, &args[0])?.pop().unwrap(),
+        "butfirst" | "bf" => {
+            let mut items = non_empty(name, &args[0])?;
+            items.remove(0);
+            rebuild(&args[0], items)
+        }
+        "butlast" | "bl" => {
+            let mut items = non_empty(name, &args[0])?;
+            items.pop();
+            rebuild(&args[0], items)
+        }
+        "count" => Value::Number(items(name, &args[0])?.len() as f64),
+        "emptyp" => Value::Bool(items(name, &args[0])?.is_empty()),
+        // items are numbered from 1
+        "item" => {
+            let index = args[0].as_number(name)?;
+            let mut items = items(name, &args[1])?;
+            if index.fract() != 0.0 || index < 1.0 || index > items.len() as f64 {
+                return Err(bad_input(name, &args[0]));
+            }
+            items.swap_remove(index as usize - 1)
+        }
+        "fput" | "lput" => {
+            let mut items = match &args[1] {
+                Value::List(items) => items.clone(),
+                // word can get only a single character
+                word => match args[0].as_word(name) {
+                    Ok(c) if c.chars().count() == 1 => items(name, word)?,
+                    _ => return Err(bad_input(name, &args[0])),
+                },
+            };
+            if name == "fput" {
+                items.insert(0, args[0].clone());
+            } else {
+                items.push(args[0].clone());
+            }
+            rebuild(&args[1], items)
+        }
+        // members of a word are its single characters, so memberp "el "hello is false
+        "memberp" => Value::Bool(items(name, &args[1])?.iter().any(|item| item.equals(&args[0]))),
+        "list" => Value::List(args.to_vec()),
+        // like list, but items of lists are joined instead of the lists
+        "sentence" | "se" => Value::List(args.iter().flat_map(|arg| match arg {
+            Value::List(items) => items.clone(),
+            arg => vec![arg.clone()],
+        }).collect()),
+        "pick" => {
+            let mut items = non_empty(name, &args[0])?;
+            let index = env.rng().gen_range(0..items.len());
+            items.swap_remove(index)
+        }
+        _ => unreachable!("{} is not a list primitive", name),
+    };
+    Ok(value)
+}
@@ -1,3 +1,12 @@
 pub mod environment;
 pub mod turtle;
-pub mod eval;
\ No newline at end of file
+pub mod eval;
+pub mod limits;
+pub mod compile;
+pub mod vm;
+pub mod optimize;
+pub mod value;
+pub mod lists;
+pub mod output;
+pub mod templates;
+pub mod definitions;
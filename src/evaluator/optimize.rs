@@ -0,0 +1,259 @@
+// Optimizations of the AST, done once before running (opt-in with Config::optimize):
+// - constant expressions are folded: `rt 4 * 90` becomes `rt 360`
+// - small repeats with constant number of iterations are unrolled, repcount is replaced by the iteration
+// - ifs with constant conditions are replaced by their instructions, or removed
+// - calls of trivial procedures (small, without calls, stop and output) are replaced by their bodies
+// Programs without errors draw the same as without optimizations, only the number of steps counted for limits changes.
+
+use std::collections::HashMap;
+
+use crate::parser::block::{Block, Condition};
+use crate::parser::command::Command;
+use crate::parser::unit::Unit;
+
+// repeat is unrolled, when it gives at most that many blocks
+const UNROLL_LIMIT: usize = 16;
+// procedure is inlined, when its body has at most that many blocks
+const INLINE_LIMIT: usize = 8;
+
+// procedure that can be inlined: params and body
+type Trivial = (Vec<String>, Vec<Block>);
+
+// what is replaced in units of a block
+enum Rule<'a> {
+    RepCount(f64),                      // repcount of the unrolled repeat
+    Params(&'a HashMap<&'a str, Unit>), // parameters of the inlined procedure
+}
+
+fn fold_unit(unit: Unit) -> Unit {
+    match unit {
+        Unit::Exp(l, o, r) => match (fold_unit(*l), fold_unit(*r)) {
+            (Unit::Val(l), Unit::Val(r)) => Unit::Val(o.apply(l, r)),
+            (l, r) => Unit::Exp(Box::new(l), o, Box::new(r)),
+        },
+        Unit::Random(bound) => Unit::Random(Box::new(fold_unit(*bound))),
+        Unit::RandomRange(low, high) => Unit::RandomRange(Box::new(fold_unit(*low)), Box::new(fold_unit(*high))),
+        Unit::RandomFloat(bound) => Unit::RandomFloat(Box::new(fold_unit(*bound))),
+        Unit::Call(name, args) => Unit::Call(name, args.into_iter().map(fold_unit).collect()),
+        unit => unit,
+    }
+}
+
+fn replace_unit(unit: Unit, rule: &Rule) -> Unit {
+    let replace = |unit: Box<Unit>| Box::new(replace_unit(*unit, rule));
+    match (unit, rule) {
+        (Unit::RepCount, Rule::RepCount(i)) => Unit::Val(*i),
+        (Unit::Var(name), Rule::Params(args)) => match args.get(name.as_str()) {
+            Some(arg) => arg.clone(),
+            None => Unit::Var(name),
+        },
+        (Unit::Exp(l, o, r), _) => Unit::Exp(replace(l), o, replace(r)),
+        (Unit::Random(bound), _) => Unit::Random(replace(bound)),
+        (Unit::RandomRange(low, high), _) => Unit::RandomRange(replace(low), replace(high)),
+        (Unit::RandomFloat(bound), _) => Unit::RandomFloat(replace(bound)),
+        (Unit::Call(name, args), _) => Unit::Call(name, args.into_iter().map(|arg| replace_unit(arg, rule)).collect()),
+        (unit, _) => unit,
+    }
+}
+
+fn map_command(command: Command, f: &dyn Fn(Unit) -> Unit) -> Command {
+    match command {
+        Command::Forward(unit) => Command::Forward(f(unit)),
+        Command::Left(unit) => Command::Left(f(unit)),
+        Command::Right(unit) => Command::Right(f(unit)),
+        Command::Back(unit) => Command::Back(f(unit)),
+        Command::Wait(unit) => Command::Wait(f(unit)),
+        Command::SetTurtle(unit) => Command::SetTurtle(f(unit)),
+        Command::SetColor(unit) => Command::SetColor(f(unit)),
+        Command::Print(unit) => Command::Print(f(unit)),
+        Command::Show(unit) => Command::Show(f(unit)),
+        Command::Type(unit) => Command::Type(f(unit)),
+        Command::Output(unit) => Command::Output(f(unit)),
+        Command::Rerandom(seed) => Command::Rerandom(seed.map(f)),
+        command => command,
+    }
+}
+
+fn map_condition(cond: Condition, f: &dyn Fn(Unit) -> Unit) -> Condition {
+    Condition { left: f(cond.left), operator: cond.operator, right: f(cond.right) }
+}
+
+fn replace_block(block: Block, rule: &Rule) -> Block {
+    let f = |unit| replace_unit(unit, rule);
+    match block {
+        Block::Single(c) => Block::Single(map_command(c, &f)),
+        Block::If(c, body) => Block::If(map_condition(c, &f), body.into_iter().map(|b| replace_block(b, rule)).collect()),
+        // nested repeat has its own repcount
+        Block::Repeat(u, body) => match rule {
+            Rule::RepCount(_) => Block::Repeat(f(u), body),
+            Rule::Params(_) => Block::Repeat(f(u), body.into_iter().map(|b| replace_block(b, rule)).collect()),
+        },
+        Block::Call(name, args) => Block::Call(name, args.into_iter().map(f).collect()),
+        block => block,
+    }
+}
+
+// number of blocks, nested ones included
+fn size(blocks: &[Block]) -> usize {
+    blocks.iter().map(|block| match block {
+        Block::Repeat(_, body) | Block::If(_, body) | Block::Function(_, _, body) => 1 + size(body),
+        _ => 1,
+    }).sum()
+}
+
+// true when some block (also nested) matches
+fn any_block(blocks: &[Block], f: &dyn Fn(&Block) -> bool) -> bool {
+    blocks.iter().any(|block| f(block) || match block {
+        Block::Repeat(_, body) | Block::If(_, body) | Block::Function(_, _, body) => any_block(body, f),
+        _ => false,
+    })
+}
+
+fn has_call(unit: &Unit) -> bool {
+    match unit {
+        Unit::Call(..) => true,
+        Unit::Exp(l, _, r) | Unit::RandomRange(l, r) => has_call(l) || has_call(r),
+        Unit::Random(u) | Unit::RandomFloat(u) => has_call(u),
+        _ => false,
+    }
+}
+
+// units of a single block (without the nested blocks)
+pub(crate) fn units(block: &Block) -> Vec<&Unit> {
+    match block {
+        Block::Single(Command::Forward(u) | Command::Left(u) | Command::Right(u) | Command::Back(u)
+            | Command::Wait(u) | Command::SetTurtle(u) | Command::SetColor(u) | Command::Rerandom(Some(u))
+            | Command::Print(u) | Command::Show(u) | Command::Type(u) | Command::Output(u)) => vec![u],
+        Block::Repeat(u, _) => vec![u],
+        Block::If(c, _) => vec![&c.left, &c.right],
+        Block::Call(_, args) => args.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+// reporters that run lists (instructions or templates)
+const RUNNERS: [&str; 7] = ["run", "runresult", "apply", "invoke", "map", "filter", "reduce"];
+
+// true when the unit (or its part) runs a list
+fn runs_list(unit: &Unit) -> bool {
+    match unit {
+        Unit::Call(name, args) => RUNNERS.contains(&name.as_str()) || args.iter().any(runs_list),
+        Unit::Exp(l, _, r) | Unit::RandomRange(l, r) => runs_list(l) || runs_list(r),
+        Unit::Random(u) | Unit::RandomFloat(u) => runs_list(u),
+        _ => false,
+    }
+}
+
+// repcount can be read by called procedure too (scope is dynamic), or by instructions in a list given to run,
+// so loops with calls are not unrolled
+fn unrollable(body: &[Block]) -> bool {
+    !any_block(body, &|block| {
+        matches!(block, Block::Call(..) | Block::Function(..)) || units(block).into_iter().any(runs_list)
+    })
+}
+
+fn trivial(body: &[Block]) -> bool {
+    size(body) <= INLINE_LIMIT && !any_block(body, &|block| {
+        matches!(block, Block::Call(..) | Block::Function(..) | Block::Single(Command::Stop() | Command::Output(_)))
+            || units(block).into_iter().any(has_call)
+    })
+}
+
+// argument can be copied to every use of the parameter, when it's the same every time it's evaluated
+fn pure(unit: &Unit) -> bool {
+    match unit {
+        Unit::Val(_) | Unit::Var(_) | Unit::Word(_) | Unit::List(_) => true,
+        Unit::Exp(l, _, r) => pure(l) && pure(r),
+        _ => false,
+    }
+}
+
+fn count_definitions<'a>(blocks: &'a [Block], definitions: &mut HashMap<&'a str, usize>) {
+    for block in blocks {
+        if let Block::Function(name, _, _) = block {
+            *definitions.entry(name.as_str()).or_default() += 1;
+        }
+        if let Block::Repeat(_, body) | Block::If(_, body) | Block::Function(_, _, body) = block {
+            count_definitions(body, definitions);
+        }
+    }
+}
+
+// procedures defined once in the whole program, at its top level, that can be inlined
+fn trivial_procedures(blocks: &[Block]) -> HashMap<String, Trivial> {
+    let mut definitions = HashMap::new();
+    count_definitions(blocks, &mut definitions);
+
+    blocks.iter().filter_map(|block| match block {
+        Block::Function(name, params, body) if definitions.get(name.as_str()) == Some(&1) && trivial(body) => {
+            Some((name.clone(), (params.clone(), body.clone())))
+        }
+        _ => None,
+    }).collect()
+}
+
+fn optimize_blocks(blocks: Vec<Block>, known: &HashMap<String, Trivial>) -> Vec<Block> {
+    let mut optimized = Vec::new();
+    for block in blocks {
+        optimize_block(block, known, &mut optimized);
+    }
+    optimized
+}
+
+// pushes optimized block (or blocks, that replace it) to `out`
+fn optimize_block(block: Block, known: &HashMap<String, Trivial>, out: &mut Vec<Block>) {
+    match block {
+        Block::Single(c) => out.push(Block::Single(map_command(c, &fold_unit))),
+        Block::If(c, body) => {
+            let c = map_condition(c, &fold_unit);
+            match (&c.left, &c.right) {
+                (Unit::Val(l), Unit::Val(r)) => {
+                    if c.operator.apply(*l, *r) {
+                        out.extend(optimize_blocks(body, known));
+                    }
+                }
+                _ => out.push(Block::If(c, optimize_blocks(body, known))),
+            }
+        },
+        Block::Repeat(u, body) => {
+            let u = fold_unit(u);
+            let body = optimize_blocks(body, known);
+            // like in eval, there are iterations while i < n
+            let iterations = match u {
+                Unit::Val(n) if n > 0.0 => n.ceil(),
+                Unit::Val(_) => 0.0,
+                _ => f64::INFINITY,
+            };
+            match u {
+                Unit::Val(_) if unrollable(&body) && iterations * size(&body) as f64 <= UNROLL_LIMIT as f64 => {
+                    for i in 1..=iterations as usize {
+                        for block in &body {
+                            optimize_block(replace_block(block.clone(), &Rule::RepCount(i as f64)), known, out);
+                        }
+                    }
+                }
+                u => out.push(Block::Repeat(u, body)),
+            }
+        },
+        Block::Function(name, params, body) => out.push(Block::Function(name, params, optimize_blocks(body, known))),
+        Block::Call(name, args) => {
+            let args: Vec<Unit> = args.into_iter().map(fold_unit).collect();
+            match known.get(&name) {
+                Some((params, body)) if params.len() == args.len() && args.iter().all(pure) => {
+                    let args: HashMap<&str, Unit> = params.iter().map(|p| p.as_str()).zip(args).collect();
+                    for block in body {
+                        optimize_block(replace_block(block.clone(), &Rule::Params(&args)), known, out);
+                    }
+                }
+                _ => out.push(Block::Call(name, args)),
+            }
+        },
+    }
+}
+
+// top-level definitions run before the rest of the program (evaluator/definitions),
+// so trivial procedures are inlined also above their definition
+pub fn optimize(blocks: Vec<Block>) -> Vec<Block> {
+    let known = trivial_procedures(&blocks);
+    optimize_blocks(blocks, &known)
+}
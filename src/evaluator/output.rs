@@ -0,0 +1,42 @@
+use std::io::Write;
+
+// where print, show and type write their text:
+// the command line prints it right away, the library keeps it in a buffer, so it can be read after the run
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    Stdout,
+    Buffer(String),
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output::Buffer(String::new())
+    }
+}
+
+impl Output {
+    pub fn write(&mut self, text: &str) {
+        match self {
+            Output::Stdout => {
+                print!("{}", text);
+                std::io::stdout().flush().ok(); // type doesn't end the line
+            }
+            Output::Buffer(buffer) => buffer.push_str(text),
+        }
+    }
+
+    // text written so far (nothing is kept for stdout)
+    pub fn text(&self) -> &str {
+        match self {
+            Output::Stdout => "",
+            Output::Buffer(buffer) => buffer,
+        }
+    }
+
+    pub fn take(&mut self) -> String {
+        match self {
+            Output::Stdout => String::new(),
+            Output::Buffer(buffer) => std::mem::take(buffer),
+        }
+    }
+}
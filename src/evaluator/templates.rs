@@ -0,0 +1,137 @@
+// Higher-order procedures: map, filter, reduce (reporters) and foreach (command).
+// They take name of a procedure ("first) or a template - list with ? in place of the input:
+// (map [? * 2] [1 2 3]), (reduce [?1 + ?2] [1 2 3]), foreach [3 4 5] [repeat ? [fd 10 rt 360 / ?]].
+// Inputs are written into the template, which is then run like with run, so it sees variables of the caller.
+
+use crate::error::LogoError;
+use super::environment::Env;
+use super::eval::{apply_command, call_reporter, check_arity, run_expression, run_instructions, Flow};
+use super::turtle::Turtle;
+use super::value::Value;
+
+pub fn is_higher_order(name: &str) -> bool {
+    matches!(name, "map" | "filter" | "reduce" | "foreach")
+}
+
+// index of the input, that goes in place of the word: ? and ?1 are the first input, ?2 the second...
+fn slot(word: &str) -> Option<usize> {
+    match word.strip_prefix('?')? {
+        "" => Some(0),
+        n => n.parse::<usize>().ok().filter(|n| *n > 0).map(|n| n - 1),
+    }
+}
+
+// list after `items` is a template of other map, filter... called inside of the template:
+// (map [? * 2] ?), apply [? + 1] :x, foreach :list [print ?]
+fn is_nested_template(items: &[Value]) -> bool {
+    match items {
+        [.., Value::Word(name)] => matches!(name.as_str(), "map" | "filter" | "reduce" | "apply" | "invoke"),
+        [.., Value::Word(name), _] => name == "foreach",
+        _ => false,
+    }
+}
+
+// template with the inputs put in place of their slots, also in nested lists (like bodies of repeat and if),
+// but not in nested templates, which get their own inputs when they are run
+fn fill(template: &Value, inputs: &[Value]) -> Value {
+    match template {
+        Value::List(items) => Value::List(items.iter().enumerate().map(|(i, item)| match item {
+            Value::List(_) if is_nested_template(&items[..i]) => item.clone(),
+            item => fill(item, inputs),
+        }).collect()),
+        Value::Word(word) => match slot(word).and_then(|i| inputs.get(i)) {
+            Some(input) => input.quoted(),
+            None => template.clone(),
+        },
+        value => value.clone(),
+    }
+}
+
+// value of the template, or of the reporter with the given name, for the inputs
+pub fn call_template(name: &str, template: &Value, inputs: Vec<Value>, turtle: &mut Turtle, env: &mut Env) -> Result<Value, LogoError> {
+    match template {
+        Value::List(_) => {
+            run_expression(name, &fill(template, &inputs), turtle, env)?.ok_or_else(|| LogoError::NoOutput(name.to_string()))
+        }
+        procedure => call_reporter(&procedure.as_word(name)?.to_lowercase(), inputs, turtle, env),
+    }
+}
+
+// runs the template, or the command with the given name, for the inputs
+pub fn run_template(name: &str, template: &Value, inputs: Vec<Value>, turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    match template {
+        Value::List(_) => run_instructions(name, &fill(template, &inputs), turtle, env),
+        procedure => apply_command(&procedure.as_word(name)?.to_lowercase(), inputs, turtle, env),
+    }
+}
+
+// None when `name` is not map, filter or reduce
+pub fn call_higher_order(name: &str, args: &[Value], turtle: &mut Turtle, env: &mut Env) -> Option<Result<Value, LogoError>> {
+    if !matches!(name, "map" | "filter" | "reduce") {
+        return None;
+    }
+    if let Err(e) = check_arity(name, 2, args.len()) {
+        return Some(Err(e));
+    }
+    Some(higher_order(name, &args[0], &args[1], turtle, env))
+}
+
+fn higher_order(name: &str, template: &Value, list: &Value, turtle: &mut Turtle, env: &mut Env) -> Result<Value, LogoError> {
+    let items = list.as_list(name)?;
+    match name {
+        "map" => {
+            let mapped = items.iter().map(|item| call_template(name, template, vec![item.clone()], turtle, env));
+            Ok(Value::List(mapped.collect::<Result<_, _>>()?))
+        }
+        "filter" => {
+            let mut kept = Vec::new();
+            for item in items {
+                if truth(name, call_template(name, template, vec![item.clone()], turtle, env)?)? {
+                    kept.push(item.clone());
+                }
+            }
+            Ok(Value::List(kept))
+        }
+        // like in logo, from the right: (reduce [?1 - ?2] [1 2 3]) is 1 - (2 - 3)
+        _ => {
+            let (last, rest) = items.split_last().ok_or_else(|| LogoError::BadInput { name: name.to_string(), value: list.clone() })?;
+            rest.iter().rev().try_fold(last.clone(), |result, item| call_template(name, template, vec![item.clone(), result], turtle, env))
+        }
+    }
+}
+
+// filter keeps the items, for which the template gives true
+fn truth(name: &str, value: Value) -> Result<bool, LogoError> {
+    if value.equals(&Value::Bool(true)) {
+        Ok(true)
+    } else if value.equals(&Value::Bool(false)) {
+        Ok(false)
+    } else {
+        Err(LogoError::BadInput { name: name.to_string(), value })
+    }
+}
+
+// foreach [items] template: runs the template for every item; like in repeat, # (repcount) is the number of the item
+pub fn foreach(args: &[Value], turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    check_arity("foreach", 2, args.len())?;
+    let items = args[0].as_list("foreach")?.to_vec();
+    // loop scope is removed however the loop ends
+    env.push_loop();
+    let result = foreach_items(&items, &args[1], turtle, env);
+    env.pop_loop();
+    result
+}
+
+fn foreach_items(items: &[Value], template: &Value, turtle: &mut Turtle, env: &mut Env) -> Result<Flow, LogoError> {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            env.usage.step(&env.limits, turtle.lines.len())?;
+        }
+        env.next_iteration();
+        match run_template("foreach", template, vec![item.clone()], turtle, env)? {
+            Flow::Next => {}
+            flow => return Ok(flow),
+        }
+    }
+    Ok(Flow::Next)
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 #[derive(Debug, Clone)]
 pub struct Turtle {
     pub img_x: f64,
@@ -7,56 +9,87 @@ pub struct Turtle {
     pub angle: f64,
     pub pen_down: bool,
     pub pen_color: String,
+    pub pen_width: f64,
+    pub id: u32,   // currently active turtle (setturtle)
+    pub time: f64, // time of drawing in ticks (1/60 s), moved forward by wait
     pub lines: Vec<Line>,
+    inactive: HashMap<u32, TurtleState>, // properties of other turtles, while they are not active
 }
 
+// properties of single turtle, that are swapped when changing the active turtle
 #[derive(Debug, Clone)]
+struct TurtleState {
+    x: f64,
+    y: f64,
+    angle: f64,
+    pen_down: bool,
+    pen_color: String,
+    pen_width: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Line {
     pub start_x: f64,
     pub start_y: f64,
     pub end_x: f64,
     pub end_y: f64,
-    pub color: String
+    pub color: String,
+    pub width: f64,
+    pub turtle: u32, // id of turtle that drew the line
+    pub time: f64,   // when the line was drawn, in ticks
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Turn {
+    Left,  // counterclockwise
+    Right, // clockwise
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Forward,
+    Back,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pen {
+    Up,   // moving doesn't draw
+    Down,
 }
 
 impl Turtle {
     pub fn new(img_x: f64, img_y: f64) -> Self {
         Turtle {
-            img_x: img_x,
-            img_y: img_y,
+            img_x,
+            img_y,
             x: img_x / 2.0,  // start at the center of the screen
             y: img_y / 2.0,
             angle: 270.0,      // head up :)
             pen_down: true,
             pen_color: "black".to_string(),
+            pen_width: 2.0,
+            id: 0,
+            time: 0.0,
             lines: Vec::new(), // lines history
+            inactive: HashMap::new(),
         }
     }
 
-    pub fn rotate(&mut self, direction: &str, degrees: f64) {
-        if direction == "left" {
-            self.angle -= degrees;
-        } else if direction == "right" {
-            self.angle += degrees;
-        } else {
-            eprintln!("rotate: Wrong direction");
+    pub fn rotate(&mut self, turn: Turn, degrees: f64) {
+        match turn {
+            Turn::Left => self.angle -= degrees,
+            Turn::Right => self.angle += degrees,
         }
     }
 
-    pub fn go(&mut self, direction: &str, distance: f64) {
+    pub fn go(&mut self, direction: Direction, distance: f64) {
         let radian_angle = self.angle.to_radians();
-        let (new_x, new_y);
-
-        if direction == "forward" {
-            new_x = self.x + distance * radian_angle.cos();
-            new_y = self.y + distance * radian_angle.sin();
-        } else if direction == "back" {
-            new_x = self.x - distance * radian_angle.cos();
-            new_y = self.y - distance * radian_angle.sin();
-        } else {
-            eprintln!("go: Wrong direction");
-            return;
-        }
+        let distance = match direction {
+            Direction::Forward => distance,
+            Direction::Back => -distance,
+        };
+        let new_x = self.x + distance * radian_angle.cos();
+        let new_y = self.y + distance * radian_angle.sin();
 
         if self.pen_down {
             self.lines.push(Line {
@@ -64,7 +97,10 @@ impl Turtle {
                 start_y: self.y,
                 end_x: new_x,
                 end_y: new_y,
-                color: self.pen_color.clone()
+                color: self.pen_color.clone(),
+                width: self.pen_width,
+                turtle: self.id,
+                time: self.time,
             });
         }
 
@@ -72,14 +108,8 @@ impl Turtle {
         self.y = new_y;
     }
 
-    pub fn move_pen(&mut self, direction: &str) {
-        if direction == "up" {
-            self.pen_down = false;
-        } else if direction == "down" {
-            self.pen_down = true;
-        } else {
-            eprintln!("move_pen: Wrong direction");
-        }
+    pub fn move_pen(&mut self, pen: Pen) {
+        self.pen_down = pen == Pen::Down;
     }
 
     pub fn clear_screen(&mut self) {
@@ -89,4 +119,40 @@ impl Turtle {
     pub fn change_color(&mut self, color: String) {
         self.pen_color = color;
     }
+
+    pub fn wait(&mut self, ticks: f64) {
+        if ticks > 0.0 {
+            self.time += ticks;
+        }
+    }
+
+    // every turtle starts in the center, heading up; the lines history is shared
+    pub fn set_turtle(&mut self, id: u32) {
+        if id == self.id {
+            return;
+        }
+        let state = self.inactive.remove(&id).unwrap_or(TurtleState {
+            x: self.img_x / 2.0,
+            y: self.img_y / 2.0,
+            angle: 270.0,
+            pen_down: true,
+            pen_color: "black".to_string(),
+            pen_width: 2.0,
+        });
+        let previous = TurtleState {
+            x: self.x,
+            y: self.y,
+            angle: self.angle,
+            pen_down: self.pen_down,
+            pen_color: std::mem::replace(&mut self.pen_color, state.pen_color),
+            pen_width: self.pen_width,
+        };
+        self.inactive.insert(self.id, previous);
+        self.x = state.x;
+        self.y = state.y;
+        self.angle = state.angle;
+        self.pen_down = state.pen_down;
+        self.pen_width = state.pen_width;
+        self.id = id;
+    }
 }
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::error::LogoError;
+use crate::parser::unit::escape_word;
+
+// everything a logo expression can evaluate to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Word(String),     // "red
+    List(Vec<Value>), // [1 2 [red blue]]
+    Bool(bool),       // result of predicates like emptyp, the same as words "true / "false
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl Value {
+    // number, also from word that looks like one ("10 is a number in logo)
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Word(w) if !w.is_empty() && w.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c)) => w.parse().ok(),
+            _ => None,
+        }
+    }
+
+    // number expected as input of `name`
+    pub fn as_number(&self, name: &str) -> Result<f64, LogoError> {
+        self.number().ok_or_else(|| LogoError::BadInput { name: name.to_string(), value: self.clone() })
+    }
+
+    // word expected as input of `name`; numbers and booleans are words too
+    pub fn as_word(&self, name: &str) -> Result<String, LogoError> {
+        match self {
+            Value::List(_) => Err(LogoError::BadInput { name: name.to_string(), value: self.clone() }),
+            value => Ok(value.to_string()),
+        }
+    }
+
+    pub fn as_list(&self, name: &str) -> Result<&[Value], LogoError> {
+        match self {
+            Value::List(items) => Ok(items),
+            _ => Err(LogoError::BadInput { name: name.to_string(), value: self.clone() }),
+        }
+    }
+
+    // value put in an instruction list, so that running the list gives it back: words get their quote
+    pub fn quoted(&self) -> Value {
+        match self {
+            Value::Word(_) | Value::Bool(_) => Value::Word(format!("\"{}", self)),
+            value => value.clone(),
+        }
+    }
+
+    // the way lists given to run are written back as code: like print, but words are written so that parsing
+    // gives them back (spaces, brackets and other special characters escaped with \)
+    pub fn source(&self) -> String {
+        match self {
+            Value::Word(w) => escape(w),
+            Value::List(items) => items.iter().map(|item| match item {
+                Value::List(_) => format!("[{}]", item.source()),
+                item => item.source(),
+            }).collect::<Vec<_>>().join(" "),
+            value => value.to_string(),
+        }
+    }
+
+    // the way show (and error messages) write values: lists with brackets
+    pub fn show(&self) -> String {
+        match self {
+            Value::List(_) => format!("[{}]", self),
+            value => value.to_string(),
+        }
+    }
+
+    // equality like logo's equalp: numbers by value (also written as words), words ignoring case
+    pub fn equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::List(l), Value::List(r)) => l.len() == r.len() && l.iter().zip(r).all(|(l, r)| l.equals(r)),
+            (Value::List(_), _) | (_, Value::List(_)) => false,
+            (l, r) => match (l.number(), r.number()) {
+                (Some(l), Some(r)) => l == r,
+                _ => l.to_string().eq_ignore_ascii_case(&r.to_string()),
+            },
+        }
+    }
+}
+
+// ( and ) alone are parentheses in instruction lists, empty word is written between bars
+fn escape(word: &str) -> String {
+    match word {
+        "" => "||".to_string(),
+        "(" | ")" => word.to_string(),
+        _ => escape_word(word),
+    }
+}
+
+// the way print shows values: lists in brackets, but without brackets around the outermost one
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Word(w) => write!(f, "{}", w),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::List(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    match item {
+                        Value::List(_) => write!(f, "[{}]", item)?,
+                        item => write!(f, "{}", item)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,294 @@
+// Stack machine running bytecode from evaluator/compile. Procedure calls don't use the rust stack,
+// frames are kept in a vector, so deep recursion is limited only by Limits::max_depth.
+
+use rand::seq::SliceRandom;
+
+use crate::error::LogoError;
+use crate::parser::block::CmpOp;
+use crate::parser::unit::ArithOp;
+use super::compile::{Op, Program};
+use super::environment::{Env, Primitive};
+use super::eval::{check_arity, random, random_float, random_range, rerandom_seed, turtle_id};
+use super::turtle::{Direction, Pen, Turn, Turtle};
+use super::value::Value;
+
+struct Frame {
+    slot: usize,       // running procedure (changed by tail calls)
+    ret: usize,        // address to come back to
+    bound: Vec<usize>, // variable slots bound by the procedure (and its tail calls), unbound at return
+    loops: usize,      // loops running when procedure was called
+    reports: bool,     // called in an expression, so it has to output a value
+}
+
+struct Machine<'a> {
+    program: &'a Program,
+    stack: Vec<f64>,
+    vars: Vec<Vec<f64>>,         // values of every variable slot, innermost binding last
+    defined: Vec<Option<usize>>, // current definition of every procedure slot
+    frames: Vec<Frame>,
+    loops: Vec<(f64, f64)>,      // iterations and iterations done of every running repeat
+}
+
+impl Machine<'_> {
+    fn pop(&mut self) -> f64 {
+        self.stack.pop().unwrap_or(0.0) // compiler always pushes arguments before use
+    }
+
+    fn pop_args(&mut self, count: usize) -> Vec<f64> {
+        self.stack.split_off(self.stack.len() - count)
+    }
+
+    fn pop_loop(&mut self, env: &mut Env) {
+        self.loops.pop();
+        env.pop_loop();
+    }
+
+    fn arith(&mut self, op: ArithOp) {
+        let r = self.pop();
+        let l = self.pop();
+        self.stack.push(op.apply(l, r));
+    }
+
+    fn compare(&mut self, op: CmpOp) {
+        let r = self.pop();
+        let l = self.pop();
+        self.stack.push(if op.apply(l, r) { 1.0 } else { 0.0 });
+    }
+
+    // leaves running procedure; false when there was none (stop in the main program)
+    fn leave(&mut self, pc: &mut usize, env: &mut Env) -> bool {
+        let frame = match self.frames.pop() {
+            Some(frame) => frame,
+            None => return false,
+        };
+        for slot in frame.bound {
+            self.vars[slot].pop();
+        }
+        while self.loops.len() > frame.loops {
+            self.pop_loop(env);
+        }
+        *pc = frame.ret;
+        true
+    }
+
+    // starts user procedure in a new frame
+    fn enter(&mut self, slot: usize, index: usize, args: Vec<f64>, reports: bool, pc: &mut usize, env: &mut Env) -> Result<(), LogoError> {
+        let definition = &self.program.definitions[index];
+        env.usage.check_depth(&env.limits, self.frames.len())?;
+        check_arity(&self.program.procs[slot].name, definition.params.len(), args.len())?;
+        for (param, arg) in definition.params.iter().zip(args) {
+            self.vars[*param].push(arg);
+        }
+        self.frames.push(Frame { slot, ret: *pc, bound: definition.params.clone(), loops: self.loops.len(), reports });
+        *pc = definition.entry;
+        Ok(())
+    }
+
+    fn call(&mut self, slot: usize, argc: usize, pc: &mut usize, turtle: &mut Turtle, env: &mut Env) -> Result<(), LogoError> {
+        let args = self.pop_args(argc);
+        let name = &self.program.procs[slot].name;
+        match (self.defined[slot], &self.program.procs[slot].primitive) {
+            (Some(index), _) => self.enter(slot, index, args, false, pc, env)?,
+            (None, Some(Primitive::Procedure(arity, f))) => {
+                check_arity(name, *arity, args.len())?;
+                f(turtle, &args).map_err(|message| LogoError::Native { name: name.clone(), message })?;
+            }
+            (None, Some(Primitive::Reporter(..))) => return Err(LogoError::NotACommand(name.clone())),
+            (None, None) => return Err(LogoError::UnknownProcedure(name.clone())),
+        }
+        Ok(())
+    }
+
+    // replaces running procedure by the called one, when it's user procedure; it runs in the frame of the running one
+    // (like Env::tail_frame) and returns straight to its caller, so tail recursion doesn't make the stack grow
+    fn tail_call(&mut self, slot: usize, argc: usize, pc: &mut usize, turtle: &mut Turtle, env: &mut Env) -> Result<(), LogoError> {
+        let index = match (self.defined[slot], self.frames.last()) {
+            (Some(index), Some(frame)) if !frame.reports => index,
+            // primitive, or error; Return after it leaves the procedure
+            // (value of a reporter can't come from a command called at its end, so that's not a tail call either)
+            _ => return self.call(slot, argc, pc, turtle, env),
+        };
+        let definition = &self.program.definitions[index];
+        check_arity(&self.program.procs[slot].name, definition.params.len(), argc)?;
+        let args = self.pop_args(argc);
+        let frame = self.frames.last_mut().unwrap();
+        frame.slot = slot;
+        for (param, arg) in definition.params.iter().zip(args) {
+            if frame.bound.contains(param) {
+                if let Some(value) = self.vars[*param].last_mut() {
+                    *value = arg;
+                }
+            } else {
+                self.vars[*param].push(arg);
+                frame.bound.push(*param);
+            }
+        }
+        *pc = definition.entry;
+        Ok(())
+    }
+
+    // user procedure called here pushes its value with Output, after it returns
+    fn report(&mut self, slot: usize, argc: usize, pc: &mut usize, env: &mut Env) -> Result<(), LogoError> {
+        let args = self.pop_args(argc);
+        let name = &self.program.procs[slot].name;
+        match (&self.program.procs[slot].primitive, self.defined[slot]) {
+            (Some(Primitive::Reporter(arity, f)), _) => {
+                check_arity(name, *arity, args.len())?;
+                let value = f(&args).map_err(|message| LogoError::Native { name: name.clone(), message })?;
+                self.stack.push(value);
+                Ok(())
+            }
+            (Some(Primitive::Procedure(..)), _) => Err(LogoError::NoOutput(name.clone())),
+            (None, Some(index)) => self.enter(slot, index, args, true, pc, env),
+            (None, None) => Err(LogoError::UnknownProcedure(name.clone())),
+        }
+    }
+
+    // name of the running procedure, for errors
+    fn running(&self) -> String {
+        self.frames.last().map(|frame| self.program.procs[frame.slot].name.clone()).unwrap_or_default()
+    }
+
+    fn execute(&mut self, turtle: &mut Turtle, env: &mut Env) -> Result<(), LogoError> {
+        let code = &self.program.code;
+        let mut pc = 0;
+        loop {
+            let op = &code[pc];
+            pc += 1;
+            match op {
+                Op::Push(n) => self.stack.push(*n),
+                Op::Load(slot) => match self.vars[*slot].last() {
+                    Some(value) => self.stack.push(*value),
+                    None => return Err(LogoError::UnknownVariable(self.program.vars[*slot].clone())),
+                },
+                Op::RepCount => self.stack.push(env.repcount()),
+                Op::Arith(op) => self.arith(*op),
+                Op::Compare(op) => self.compare(*op),
+                Op::Random => {
+                    let bound = self.pop();
+                    self.stack.push(random(bound, env)?);
+                }
+                Op::RandomRange => {
+                    let high = self.pop();
+                    let low = self.pop();
+                    self.stack.push(random_range(low, high, env)?);
+                }
+                Op::RandomFloat => {
+                    let bound = self.pop();
+                    self.stack.push(random_float(bound, env)?);
+                }
+                Op::Report(slot, argc) => self.report(*slot, *argc, &mut pc, env)?,
+
+                Op::Forward => turtle.go(Direction::Forward, self.pop()),
+                Op::Back => turtle.go(Direction::Back, self.pop()),
+                Op::Left => turtle.rotate(Turn::Left, self.pop()),
+                Op::Right => turtle.rotate(Turn::Right, self.pop()),
+                Op::Wait => turtle.wait(self.pop()),
+                Op::SetTurtle => turtle.set_turtle(turtle_id(self.pop())?),
+                Op::ClearScreen => turtle.clear_screen(),
+                Op::PenUp => turtle.move_pen(Pen::Up),
+                Op::PenDown => turtle.move_pen(Pen::Down),
+                Op::SetColor(index) => turtle.change_color(self.program.colors[*index].clone()),
+                Op::SetColorPick(index) => {
+                    match self.program.picks[*index].choose(&mut *env.rng()) {
+                        Some(random_color) => turtle.change_color(random_color.to_string()),
+                        None => return Err(LogoError::BadInput { name: "pick".to_string(), value: Value::List(vec![]) }),
+                    }
+                }
+                Op::Print => {
+                    let text = format!("{}\n", Value::Number(self.pop()));
+                    env.output.write(&text);
+                }
+                Op::Type => {
+                    let text = Value::Number(self.pop()).to_string();
+                    env.output.write(&text);
+                }
+                Op::Rerandom(with_seed) => {
+                    let seed = if *with_seed { rerandom_seed(self.pop())? } else { env.seed() };
+                    env.reseed(seed);
+                }
+
+                Op::Step => env.usage.step(&env.limits, turtle.lines.len())?,
+                Op::Jump(target) => pc = *target,
+                Op::JumpIfFalse(target) => {
+                    if self.pop() == 0.0 {
+                        pc = *target;
+                    }
+                }
+                Op::LoopStart => {
+                    let n = self.pop();
+                    self.loops.push((n, 0.0));
+                    env.push_loop();
+                }
+                Op::LoopNext(exit) => {
+                    let (n, done) = self.loops[self.loops.len() - 1];
+                    if done < n {
+                        if done > 0.0 {
+                            env.usage.step(&env.limits, turtle.lines.len())?; // loops with empty effect count too
+                        }
+                        env.next_iteration();
+                        self.loops.last_mut().unwrap().1 += 1.0;
+                    } else {
+                        self.pop_loop(env);
+                        pc = *exit;
+                    }
+                }
+                Op::Define(index) => {
+                    let definition = &self.program.definitions[*index];
+                    self.defined[definition.slot] = Some(*index);
+                    env.set_fun(self.program.procs[definition.slot].name.clone(), definition.block.clone());
+                }
+                Op::Call(slot, argc) => self.call(*slot, *argc, &mut pc, turtle, env)?,
+                Op::TailCall(slot, argc) => self.tail_call(*slot, *argc, &mut pc, turtle, env)?,
+                Op::Stop | Op::Return => {
+                    if self.frames.last().is_some_and(|frame| frame.reports) {
+                        return Err(LogoError::NoOutput(self.running()));
+                    }
+                    if !self.leave(&mut pc, env) {
+                        return Ok(());
+                    }
+                }
+                Op::Output => {
+                    let value = self.pop();
+                    match self.frames.last() {
+                        None => return Err(LogoError::NotInProcedure("output".to_string())),
+                        Some(frame) if !frame.reports => return Err(LogoError::NotACommand(self.running())),
+                        Some(_) => {
+                            self.leave(&mut pc, env);
+                            self.stack.push(value);
+                        }
+                    }
+                }
+                Op::Halt => return Ok(()),
+            }
+        }
+    }
+}
+
+// variables set before (by earlier runs) are visible to the program
+pub fn run(program: &Program, turtle: &mut Turtle, env: &mut Env) -> Result<(), LogoError> {
+    let mut vars = Vec::new();
+    for name in &program.vars {
+        vars.push(match env.get_var(name) {
+            Ok(value) => match value.number() {
+                Some(n) => vec![n],
+                None => return Err(LogoError::Unsupported(format!("word or list in :{}", name))),
+            },
+            Err(_) => Vec::new(),
+        });
+    }
+    let mut machine = Machine {
+        program,
+        stack: Vec::new(),
+        vars,
+        defined: program.procs.iter().map(|proc| proc.defined).collect(),
+        frames: Vec::new(),
+        loops: Vec::new(),
+    };
+    let result = machine.execute(turtle, env);
+    // loop scopes in Env are removed however the program ends
+    while !machine.loops.is_empty() {
+        machine.pop_loop(env);
+    }
+    result
+}
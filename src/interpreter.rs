@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::error::LogoError;
+use crate::evaluator::{compile::{compile, Program}, environment::{Env, Primitive}, eval::{arities, eval}, limits::{Limits, Usage}, optimize::optimize, output::Output, turtle::Turtle, vm};
+use crate::parser::block::Block;
+use crate::parser::format::format_with;
+use crate::parser::program::parse_with;
+
+pub use crate::parser::program::parse;
+
+// settings of the interpreter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub width: f64,  // size of the canvas, turtle starts in its center
+    pub height: f64,
+    pub limits: Limits, // checked separately for every run
+    pub seed: Option<u64>, // seed of random numbers; the same seed gives the same drawing, None = different every time
+    pub optimize: bool, // run programs through evaluator/optimize first
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { width: 800.0, height: 600.0, limits: Limits::default(), seed: None, optimize: false }
+    }
+}
+
+// Logo interpreter, that can be embedded in other programs:
+// procedures and turtle state are kept between runs, until reset
+pub struct Interpreter {
+    config: Config,
+    turtle: Turtle,
+    env: Env,
+}
+
+impl Interpreter {
+    pub fn new(config: Config) -> Self {
+        let mut env = match config.seed {
+            Some(seed) => Env::with_seed(seed),
+            None => Env::new(),
+        };
+        env.limits = config.limits.clone();
+        Interpreter {
+            turtle: Turtle::new(config.width, config.height),
+            env,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    // drawing made so far
+    pub fn turtle(&self) -> &Turtle {
+        &self.turtle
+    }
+
+    // seed of random numbers used by the interpreter (chosen randomly, when Config::seed is None)
+    pub fn seed(&self) -> u64 {
+        self.env.seed()
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.env.set_seed(seed);
+    }
+
+    // where print, show and type write; a buffer by default, Output::Stdout in the command line
+    pub fn set_output(&mut self, output: Output) {
+        self.env.output = output;
+    }
+
+    // text printed so far (when the output is a buffer)
+    pub fn output(&self) -> &str {
+        self.env.output.text()
+    }
+
+    // text printed so far, the buffer is emptied
+    pub fn take_output(&mut self) -> String {
+        self.env.output.take()
+    }
+
+    pub fn into_turtle(self) -> Turtle {
+        self.turtle
+    }
+
+    // clears the drawing and printed text, and forgets all procedures and variables (registered primitives are kept);
+    // random numbers start over from the same seed
+    pub fn reset(&mut self) {
+        self.turtle = Turtle::new(self.config.width, self.config.height);
+        self.env.output.take();
+        self.env.clear();
+    }
+
+    // makes rust function callable from logo like user procedure: `name arg1 arg2 ...`;
+    // names of built-in commands (fd, rt, ...) can't be used, and user procedures with the same name take precedence
+    pub fn register_procedure<F>(&mut self, name: &str, arity: usize, procedure: F)
+    where
+        F: Fn(&mut Turtle, &[f64]) -> Result<(), String> + 'static,
+    {
+        self.env.set_primitive(name.to_string(), Primitive::Procedure(arity, Rc::new(procedure)));
+    }
+
+    // rust function that returns a value, used in expressions in parentheses: `fd (sensor 1) * 2`
+    pub fn register_reporter<F>(&mut self, name: &str, arity: usize, reporter: F)
+    where
+        F: Fn(&[f64]) -> Result<f64, String> + 'static,
+    {
+        self.env.set_primitive(name.to_string(), Primitive::Reporter(arity, Rc::new(reporter)));
+    }
+
+    pub fn run_blocks(&mut self, blocks: Vec<Block>) -> Result<(), LogoError> {
+        let blocks = if self.config.optimize { optimize(blocks) } else { blocks };
+        self.env.usage = Usage::default();
+        eval(blocks, &mut self.turtle, &mut self.env)
+    }
+
+    // bytecode for the stack machine; procedures defined by earlier runs are compiled in too
+    pub fn compile(&self, blocks: &[Block]) -> Result<Program, LogoError> {
+        if self.config.optimize {
+            return compile(&optimize(blocks.to_vec()), &self.env);
+        }
+        compile(blocks, &self.env)
+    }
+
+    // same as run_blocks, but compiled to bytecode first and run on the stack machine (evaluator/vm)
+    pub fn run_compiled(&mut self, blocks: &[Block]) -> Result<(), LogoError> {
+        let program = self.compile(blocks)?;
+        self.env.usage = Usage::default();
+        vm::run(&program, &mut self.turtle, &mut self.env)
+    }
+
+    // like parse, but procedures defined by earlier runs and registered primitives are known to the parser too,
+    // so they can be called without parentheses
+    pub fn parse(&self, code: &str) -> Result<Vec<Block>, LogoError> {
+        parse_with(code, &arities(&self.env))
+    }
+
+    // code formatted by parser/format, that this interpreter parses back to the same blocks
+    pub fn format(&self, blocks: &[Block]) -> String {
+        format_with(blocks, &arities(&self.env))
+    }
+
+    pub fn run_str(&mut self, code: &str) -> Result<(), LogoError> {
+        let blocks = self.parse(code)?;
+        self.run_blocks(blocks)
+    }
+
+    pub fn run_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LogoError> {
+        let path = path.as_ref();
+        let code = fs::read_to_string(path)
+            .map_err(|e| LogoError::Io(format!("Error reading file {}: {}", path.display(), e)))?;
+        self.run_str(&code)
+    }
+}
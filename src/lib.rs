@@ -1,3 +1,8 @@
 pub mod parser;
 pub mod evaluator;
-pub mod drawing;
\ No newline at end of file
+pub mod drawing;
+pub mod error;
+pub mod interpreter;
+
+pub use error::LogoError;
+pub use interpreter::{Config, Interpreter};
@@ -1,10 +1,26 @@
-mod parser;
-mod evaluator;
-mod drawing;
+use lista7::evaluator::optimize::optimize;
+use lista7::{drawing, Config, Interpreter};
+use lista7::evaluator::output::Output;
 use std::fs;
+use std::path::Path;
+
+// settings from the command line
+struct Options {
+    format: String,
+    mm_per_step: f64,
+    dump_ast: bool,
+    dump_optimized: bool,
+    optimize: bool,
+    seed: Option<u64>,
+    vm: bool, // run on the bytecode stack machine instead of the tree-walking evaluator
+    fmt: bool,       // print programs in the canonical style instead of drawing them
+    fmt_check: bool, // only check that programs are in the canonical style
+}
 
 // assuming input file name and output file name are the same (but in different directiories)
-pub fn create_image(file_name: &str, size_x: f64, size_y: f64) {
+fn create_image(file_name: &str, size_x: f64, size_y: f64, options: &Options) {
+    let mut interpreter = Interpreter::new(Config { width: size_x, height: size_y, seed: options.seed, optimize: options.optimize, ..Config::default() });
+    interpreter.set_output(Output::Stdout);
 
     // read from file
     let filename = format!("codes/{}.txt", file_name);
@@ -15,36 +31,147 @@ pub fn create_image(file_name: &str, size_x: f64, size_y: f64) {
             return;
         }
     };
-    program_code.replace("\r\n\t", " ").trim().to_string();
-    // println!("File content: {}", program_code); // debug
 
     // parse
-    let parse = parser::program::parse_program(&program_code);
-    let parsed_program = match parse {
-        Ok((_, blocks)) => blocks,
+    let parsed_program = match interpreter.parse(&program_code) {
+        Ok(blocks) => blocks,
         Err(e) => {
-            eprintln!("{}", e);
-            Vec::new()
+            eprintln!("{}: {}", file_name, e);
+            return;
         }
     };
-    println!("{:#?}", parsed_program); // debug
+    if options.dump_ast {
+        println!("{:#?}", parsed_program); // debug
+    }
+    if options.dump_optimized {
+        println!("{:#?}", optimize(parsed_program.clone()));
+    }
 
-    // evaluate
-    let mut env = evaluator::environment::Env::new();
-    let mut turtle = evaluator::turtle::Turtle::new(size_x, size_y);
-    evaluator::eval::eval(parsed_program, &mut turtle, &mut env);
+    // evaluate; whatever was drawn before an error is saved anyway
+    let result = if options.vm { interpreter.run_compiled(&parsed_program) } else { interpreter.run_blocks(parsed_program) };
+    if let Err(e) = result {
+        eprintln!("{}: {}", file_name, e);
+    }
 
     // save
-    drawing::draw::save_image(turtle, file_name);
+    if let Err(e) = drawing::draw::save(interpreter.into_turtle(), file_name, &options.format, options.mm_per_step) {
+        eprintln!("{}", e);
+    }
 }
 
+// logofmt: prints the program formatted by parser/format, or with `check` only says if the file is formatted;
+// false when it isn't (or can't be read or parsed); parsed like the interpreter parses it when it runs
+fn format_file(file_name: &str, check: bool) -> bool {
+    let interpreter = Interpreter::new(Config::default());
+    let filename = format!("codes/{}.txt", file_name);
+    let program_code = match fs::read_to_string(filename) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Error reading file {}: {}", file_name, e);
+            return false;
+        }
+    };
+    let formatted = match interpreter.parse(&program_code) {
+        Ok(blocks) => interpreter.format(&blocks),
+        Err(e) => {
+            eprintln!("{}: {}", file_name, e);
+            return false;
+        }
+    };
+    if !check {
+        print!("{}", formatted);
+        return true;
+    }
+    if formatted != program_code {
+        eprintln!("{} is not formatted", file_name);
+        return false;
+    }
+    true
+}
 
+// render display list saved earlier with --format json, without running the program again
+fn render_json(path: &str, format: &str, mm_per_step: f64) {
+    let turtle = match drawing::json::load_json(path) {
+        Ok(turtle) => turtle,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let file_name = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("drawing");
+    if let Err(e) = drawing::draw::save(turtle, file_name, format, mm_per_step) {
+        eprintln!("{}", e);
+    }
+}
 
+// usage: lista7 [--format svg|pdf|eps|dxf|html|json|term|term-mono] [--mm-per-step N]
+//               [--from-json display_list.json ...] [--dump-ast] [--dump-optimized] [--optimize]
+//               [--seed N] [--vm] [--fmt] [--fmt-check] [names of files in codes/ ...]
 fn main() {
-    create_image("star", 800.0, 600.0);
-    create_image("squares", 800.0, 600.0);
-    create_image("tree", 800.0, 600.0);
-    create_image("fern", 800.0, 600.0);
-    create_image("spiral", 800.0, 600.0); // extra: colorful spiral
+    let mut options = Options { format: "svg".to_string(), mm_per_step: 1.0, dump_ast: false, dump_optimized: false, optimize: false, seed: None, vm: false, fmt: false, fmt_check: false };
+    let mut file_names = Vec::new();
+    let mut json_files = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--format" {
+            options.format = args.next().unwrap_or_default();
+        } else if arg == "--mm-per-step" {
+            // zero, negative or nan scale would put every line in one point
+            options.mm_per_step = match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                Some(value) if value.is_finite() && value > 0.0 => value,
+                _ => {
+                    eprintln!("--mm-per-step expects a positive number");
+                    std::process::exit(1);
+                }
+            };
+        } else if arg == "--seed" {
+            options.seed = match args.next().and_then(|v| v.parse().ok()) {
+                Some(value) => Some(value),
+                None => {
+                    eprintln!("--seed expects a non-negative integer");
+                    std::process::exit(1);
+                }
+            };
+        } else if arg == "--fmt" {
+            options.fmt = true;
+        } else if arg == "--fmt-check" {
+            options.fmt_check = true;
+        } else if arg == "--vm" {
+            options.vm = true;
+        } else if arg == "--optimize" {
+            options.optimize = true;
+        } else if arg == "--dump-optimized" {
+            options.dump_optimized = true;
+        } else if arg == "--dump-ast" {
+            options.dump_ast = true;
+        } else if arg == "--from-json" {
+            json_files.extend(args.next());
+        } else {
+            file_names.push(arg);
+        }
+    }
+    if !drawing::draw::FORMATS.contains(&options.format.as_str()) {
+        eprintln!("Unknown format '{}', expected one of: {}", options.format, drawing::draw::FORMATS.join(", "));
+        std::process::exit(1);
+    }
+
+    for path in &json_files {
+        render_json(path, &options.format, options.mm_per_step);
+    }
+    if file_names.is_empty() && json_files.is_empty() {
+        // extra: colorful spiral
+        file_names = ["star", "squares", "tree", "fern", "spiral"].iter().map(|s| s.to_string()).collect();
+    }
+    if options.fmt || options.fmt_check {
+        let formatted = file_names.iter().filter(|file_name| format_file(file_name, options.fmt_check)).count();
+        if formatted < file_names.len() {
+            std::process::exit(1);
+        }
+        return;
+    }
+    for file_name in file_names {
+        create_image(&file_name, 800.0, 600.0, &options);
+    }
 }
 
@@ -1,39 +1,71 @@
 use nom::{
     branch::alt, 
-    bytes::complete::{tag, tag_no_case, take_while1}, 
+    bytes::complete::{tag, take_while1}, 
     character::complete::{char, multispace0, space0, space1}, 
-    combinator::{map, map_res}, multi::{many1, separated_list0}, 
+    combinator::{map, map_res, opt}, multi::{count, many0, many1, separated_list0}, 
     sequence::{delimited, preceded, tuple}, 
     IResult
 };
 
 use super::command::{parse_command, Command};
-use super::unit::{Unit, parse_unit};
+use super::program::Arities;
+use super::unit::{is_name_char, keyword, parse_call as parse_reporter, parse_unit, Unit};
+
+// comparison operator of a condition
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CmpOp {
+    Less,    // <
+    Equal,   // ==
+    Greater, // >
+}
+
+impl CmpOp {
+    pub fn apply(self, l: f64, r: f64) -> bool {
+        match self {
+            CmpOp::Less => l < r,
+            CmpOp::Equal => l == r,
+            CmpOp::Greater => l > r,
+        }
+    }
+
+    // name of the operation in logo, used in error messages
+    pub fn name(self) -> &'static str {
+        match self {
+            CmpOp::Less => "lessp",
+            CmpOp::Equal => "equalp",
+            CmpOp::Greater => "greaterp",
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Condition {
     pub left: Unit,
-    pub operator: String,
+    pub operator: CmpOp,
     pub right: Unit,
 }
 
-pub fn parse_condition(input: &str) -> IResult<&str, Condition> {
+pub fn parse_condition<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Condition> {
 
-    fn parse_operator(input: &str) -> IResult<&str, String> {
+    fn parse_operator(input: &str) -> IResult<&str, CmpOp> {
         alt((
-            map(tag("<"), |s: &str| s.to_string()),
-            map(tag(">"), |s: &str| s.to_string()),
-            map(tag("=="), |s: &str| s.to_string()),
+            map(tag("<"), |_| CmpOp::Less),
+            map(tag(">"), |_| CmpOp::Greater),
+            map(tag("=="), |_| CmpOp::Equal),
         ))(input)
     }
 
-    let (input, (left, operator, right)) = tuple((
-        preceded(space0, parse_unit),
+    let (input, left) = preceded(space0, |i| parse_unit(i, arities))(input)?;
+    let (input, comparison) = opt(tuple((
         preceded(space0, parse_operator),
-        preceded(space0, parse_unit),
-    ))(input)?;
-    
-    Ok((input, Condition { left, operator, right }))
+        preceded(space0, |i| parse_unit(i, arities)),
+    )))(input)?;
+
+    match comparison {
+        Some((operator, right)) => Ok((input, Condition { left, operator, right })),
+        // predicate alone, eg if (emptyp :points) [ stop ], is the same as comparing it to true
+        None => Ok((input, Condition { left, operator: CmpOp::Equal, right: Unit::Word("true".to_string()) })),
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -45,90 +77,108 @@ pub enum Block {
     Call(String, Vec<Unit>) // name, args <- function call
 }
 
-fn parse_repeat(input: &str) -> IResult<&str, Block> {
-    let (input, _) = tag_no_case("repeat")(input)?;
+fn parse_repeat<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Block> {
+    let (input, _) = keyword("repeat")(input)?;
     let (input, _) = space1(input)?;
-    let (input, times) = parse_unit(input)?;
+    let (input, times) = parse_unit(input, arities)?;
     let (input, _) = space1(input)?;
     let (input, commands) = delimited(
         char('['), 
-        many1(preceded(multispace0, parse_block)),
+        many1(preceded(multispace0, |i| parse_block(i, arities))),
         preceded(multispace0, char(']')),
     )(input)?;
     Ok((input, Block::Repeat(times, commands)))
 }
 
-fn parse_if(input: &str) -> IResult<&str, Block> {
-    let (input, _) = tag_no_case("if")(input)?;
+fn parse_if<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Block> {
+    let (input, _) = keyword("if")(input)?;
     let (input, _) = space1(input)?;
-    let (input, condition) = parse_condition(input)?;
+    let (input, condition) = parse_condition(input, arities)?;
     let (input, _) = space1(input)?;
     let (input, commands) = delimited(
         char('['), 
-        many1(preceded(multispace0, parse_block)),
+        many1(preceded(multispace0, |i| parse_block(i, arities))),
         preceded(multispace0, char(']')),
     )(input)?;
     Ok((input, Block::If(condition, commands)))
 }
 
-fn parse_function(input: &str) -> IResult<&str, Block> {
+fn parse_function<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Block> {
 
     fn parse_params(input: &str) -> IResult<&str, Vec<String>> {
-        separated_list0(
-            space1, 
+        many0(
             preceded(
-                char(':'),
-                map(take_while1(char::is_alphanumeric), |s: &str| s.to_string())
+                tuple((space1, char(':'))),
+                map(take_while1(is_name_char), |s: &str| s.to_string())
             )
         )(input)
     }
 
-    let (input, _) = tag_no_case("to ")(input)?; 
-    let (input, name) = map(take_while1(char::is_alphanumeric), |s: &str| s.to_string())(input)?; 
+    let (input, _) = keyword("to")(input)?; 
     let (input, _) = space1(input)?; 
+    let (input, name) = map(take_while1(is_name_char), str::to_lowercase)(input)?; 
     let (input, params) = parse_params(input)?;
-    let (input, commands) = many1(preceded(multispace0, parse_block))(input)?;
-    let (input, _) = preceded(multispace0, tag_no_case("end"))(input)?;
+    let (input, commands) = many1(preceded(multispace0, |i| parse_block(i, arities)))(input)?;
+    let (input, _) = preceded(multispace0, keyword("end"))(input)?;
 
     Ok((input, Block::Function(name, params, commands)))
 }
 
-fn parse_call(input: &str) -> IResult<&str, Block> {
+fn parse_call<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Block> {
 
-    fn parse_args(input: &str) -> IResult<&str, Vec<Unit>> {
+    fn parse_args<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Vec<Unit>> {
         separated_list0(
             space1, 
-            parse_unit
+            |i| parse_unit(i, arities)
         )(input)
     }
 
     let reserved_keywords = ["to", "repeat", "if", "end", "pick", "setcolor"]; // illegal keywords
     let (input, name) = map_res(
-        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+        take_while1(is_name_char),
         |s: &str| {
-            if reserved_keywords.contains(&s) {
+            if reserved_keywords.contains(&s.to_lowercase().as_str()) {
                 Err("Reserved keyword")
+            } else if s.starts_with(|c: char| c.is_ascii_digit()) {
+                Err("Number is not a name") // input left after a call
             } else {
-                Ok(s.to_string())
+                Ok(s.to_lowercase())
             }
         },
     )(input)?;
-    let (input, _) = space0(input)?; 
-    let (input, args) = parse_args(input)?; 
+    // procedure known to the parser takes as many inputs as it has, so there can be more calls in one line;
+    // when there are less of them, all are taken, and the call fails with WrongArity
+    let known = match arities.get(&name) {
+        Some(n) => count(preceded(space1, |i| parse_unit(i, arities)), *n)(input).ok(),
+        None => None,
+    };
+    let (input, args) = match known {
+        Some(parsed) => parsed,
+        None => preceded(space0, |i| parse_args(i, arities))(input)?,
+    };
 
     Ok((input, Block::Call(name, args)))
 }
 
+// call in parentheses takes all inputs up to `)`: (proc 1 2 3)
+fn parse_paren_call<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Block> {
+    match parse_reporter(input, arities)? {
+        (input, Unit::Call(name, args)) => Ok((input, Block::Call(name, args))),
+        _ => Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify))),
+    }
+}
+
 
-pub fn parse_block(input: &str) -> IResult<&str, Block> {
+pub fn parse_block<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Block> {
     preceded(
         multispace0, // ignoruje białe znaki przed blokiem
         alt((
-            map(parse_command, Block::Single),
-            parse_repeat,
-            parse_if,
-            parse_function,
-            parse_call,
+            map(|i| parse_command(i, arities), Block::Single),
+            |i| parse_repeat(i, arities),
+            |i| parse_if(i, arities),
+            |i| parse_function(i, arities),
+            |i| parse_call(i, arities),
+            |i| parse_paren_call(i, arities),
         )),
     )(input)
 }
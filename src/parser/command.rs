@@ -1,9 +1,10 @@
 use nom::{
-    branch::alt, bytes::complete::{tag, tag_no_case, take_while1}, character::complete::{alpha0, alpha1, alphanumeric1, char, multispace0, multispace1, space1}, combinator::map, multi::{many1, separated_list1}, sequence::{delimited, preceded}, IResult
+    branch::alt, character::complete::space1, combinator::opt, sequence::preceded, IResult
 };
 use std::collections::HashSet;
 
-use super::unit::{parse_unit, Unit};
+use super::program::Arities;
+use super::unit::{keyword, parse_list, parse_unit, Unit};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Command {
@@ -15,143 +16,199 @@ pub enum Command {
     ClearScreen(),
     PenUp(),
     PenDown(),
-    SetColor(String),
+    SetColor(Unit), // color name, eg "red or (item 2 :palette)
     SetColorPick(Vec<String>),
     SetTurtle(Unit),
     Stop(),
+    Output(Unit), // ends the procedure, which gives the value
     HideTurtle(),
     ShowTurtle(),
-    Window()
+    Window(),
+    Rerandom(Option<Unit>), // seed, the interpreter's seed if not given
+    Print(Unit), // value and new line, lists without the outer brackets
+    Show(Unit),  // like print, but lists with brackets
+    Type(Unit),  // like print, without new line
 }
 
-fn parse_forward(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("forward"), tag_no_case("fd")))(input)?;
+fn parse_forward<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("forward"), keyword("fd")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, value) = parse_unit(input)?;
+    let (input, value) = parse_unit(input, arities)?;
     Ok((input, Command::Forward(value)))
 }
 
-fn parse_left(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("left"), tag_no_case("lt")))(input)?;
+fn parse_left<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("left"), keyword("lt")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, value) = parse_unit(input)?;
+    let (input, value) = parse_unit(input, arities)?;
     Ok((input, Command::Left(value)))
 }
 
-fn parse_right(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("right"), tag_no_case("rt")))(input)?;
+fn parse_right<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("right"), keyword("rt")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, value) = parse_unit(input)?;
+    let (input, value) = parse_unit(input, arities)?;
     Ok((input, Command::Right(value)))
 }
 
-fn parse_back(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("back"), tag_no_case("bk")))(input)?;
+fn parse_back<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("back"), keyword("bk")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, value) = parse_unit(input)?;
+    let (input, value) = parse_unit(input, arities)?;
     Ok((input, Command::Back(value)))
 }
 
-fn parse_wait(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("wait"), tag_no_case("wt")))(input)?;
+fn parse_wait<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("wait"), keyword("wt")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, value) = parse_unit(input)?;
+    let (input, value) = parse_unit(input, arities)?;
     Ok((input, Command::Wait(value)))
 }
 
 fn parse_clearscreen(input: &str) -> IResult<&str, Command> {
-    let (input, _) = tag_no_case("clearscreen")(input)?;
+    let (input, _) = keyword("clearscreen")(input)?;
     Ok((input, Command::ClearScreen()))
 }
 
 fn parse_penup(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("penup"), tag_no_case("pu")))(input)?;
+    let (input, _) = alt((keyword("penup"), keyword("pu")))(input)?;
     Ok((input, Command::PenUp()))
 }
 
 fn parse_pendown(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("pendown"), tag_no_case("pd")))(input)?;
+    let (input, _) = alt((keyword("pendown"), keyword("pd")))(input)?;
     Ok((input, Command::PenDown()))
 }
 
 fn parse_stop(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("stop"), tag_no_case("sp")))(input)?;
+    let (input, _) = alt((keyword("stop"), keyword("sp")))(input)?;
     Ok((input, Command::Stop()))
 }
 
+fn parse_output<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("output"), keyword("op")))(input)?;
+    let (input, _) = space1(input)?;
+    let (input, value) = parse_unit(input, arities)?;
+    Ok((input, Command::Output(value)))
+}
+
 fn parse_showturtle(input: &str) -> IResult<&str, Command> {
-    let (input, _) = tag_no_case("showturtle")(input)?;
+    let (input, _) = keyword("showturtle")(input)?;
     Ok((input, Command::ShowTurtle()))
 }
 
 fn parse_hideturtle(input: &str) -> IResult<&str, Command> {
-    let (input, _) = tag_no_case("hideturtle")(input)?;
+    let (input, _) = keyword("hideturtle")(input)?;
     Ok((input, Command::HideTurtle()))
 }
 
 fn parse_window(input: &str) -> IResult<&str, Command> {
-    let (input, _) = tag_no_case("window")(input)?;
+    let (input, _) = keyword("window")(input)?;
     Ok((input, Command::Window()))
 }
 
-fn parse_setcolor(input: &str) -> IResult<&str, Command> {
+fn parse_rerandom<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = keyword("rerandom")(input)?;
+    let (input, seed) = opt(preceded(space1, |i| parse_unit(i, arities)))(input)?;
+    Ok((input, Command::Rerandom(seed)))
+}
+
+fn is_color(name: &str) -> bool {
     let allowed_colors: HashSet<&str> = [
         "black", "blue", "green", "cyan", "red", "magenta", 
         "yellow", "white", "brown", "tan", "aqua", "salmon", 
         "purple", "orange", "gray"
     ].iter().cloned().collect();
+    allowed_colors.contains(name)
+}
+
+fn color_error(input: &str) -> nom::Err<nom::error::Error<&str>> {
+    nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag))
+}
 
-    let (input, _) = alt((tag_no_case("setcolor"), tag_no_case("sc")))(input)?;
+fn parse_setcolor<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("setcolor"), keyword("sc")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, _) = tag("\"")(input)?;
-    
-    let (input, color) = take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)?;
-
-    if allowed_colors.contains(color) {
-        Ok((input, Command::SetColor(color.to_string())))
-    } else {
-        Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag)))
+    let (input, color) = parse_unit(input, arities)?;
+
+    // color given directly can be checked already while parsing
+    match &color {
+        Unit::Word(name) if !is_color(name) => Err(color_error(input)),
+        _ => Ok((input, Command::SetColor(color))),
     }
 }
 
+// list of colors is a list literal, like in other commands: setcolor pick [red blue]; it has to contain only words
 fn parse_setcolorpick(input: &str) -> IResult<&str, Command> {
-    let (input, _) = alt((tag_no_case("setcolor"), tag_no_case("sc")))(input)?;
+    let (input, _) = alt((keyword("setcolor"), keyword("sc")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, _) = tag_no_case("pick")(input)?;
+    let (input, _) = keyword("pick")(input)?;
     let (input, _) = space1(input)?;
-    let (input, list) = delimited(
-        char('['), 
-        many1(preceded(multispace0, alpha1)),
-        preceded(multispace0, char(']')),
-    )(input)?;
-    let strings = list.into_iter().map(|s| s.to_string()).collect();
+    let (input, list) = parse_list(input)?;
+    let colors = match list {
+        Unit::List(items) if !items.is_empty() => items,
+        _ => return Err(color_error(input)),
+    };
+    let mut strings = Vec::new();
+    for color in colors {
+        match color {
+            Unit::Word(name) => strings.push(name),
+            _ => return Err(color_error(input)),
+        }
+    }
     Ok((input, Command::SetColorPick(strings))) 
 }
 
-fn parse_setturtle(input: &str) -> IResult<&str, Command> {
-    let (input, _) = tag_no_case("setturtle")(input)?;
+fn parse_setturtle<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = keyword("setturtle")(input)?;
     let (input, _) = space1(input)?;
-    let (input, value) = parse_unit(input)?;
+    let (input, value) = parse_unit(input, arities)?;
     Ok((input, Command::SetTurtle(value)))
 }
 
-pub fn parse_command(input: &str) -> IResult<&str, Command> {
+fn parse_print<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = alt((keyword("print"), keyword("pr")))(input)?;
+    let (input, _) = space1(input)?;
+    let (input, value) = parse_unit(input, arities)?;
+    Ok((input, Command::Print(value)))
+}
+
+fn parse_show<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = keyword("show")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, value) = parse_unit(input, arities)?;
+    Ok((input, Command::Show(value)))
+}
+
+fn parse_type<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
+    let (input, _) = keyword("type")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, value) = parse_unit(input, arities)?;
+    Ok((input, Command::Type(value)))
+}
+
+pub fn parse_command<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Command> {
     alt((
-        parse_forward, 
-        parse_left, 
-        parse_right, 
-        parse_back,
+        |i| parse_forward(i, arities),
+        |i| parse_left(i, arities),
+        |i| parse_right(i, arities),
+        |i| parse_back(i, arities),
         parse_clearscreen,
-        parse_wait,
+        |i| parse_wait(i, arities),
         parse_pendown,
         parse_penup,
         parse_setcolorpick,
-        parse_setcolor,
+        |i| parse_setcolor(i, arities),
         parse_stop,
+        |i| parse_output(i, arities),
         parse_showturtle,
         parse_hideturtle,
         parse_window,
-        parse_setturtle,
+        |i| parse_setturtle(i, arities),
+        |i| parse_rerandom(i, arities),
+        |i| parse_print(i, arities),
+        |i| parse_show(i, arities), // after showturtle
+        |i| parse_type(i, arities),
     ))(input)
 }
 
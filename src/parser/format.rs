@@ -0,0 +1,233 @@
+// Formatter (logofmt): writes AST back as logo code in one canonical style, so that parse(format(ast)) == ast.
+// Keywords are lowercase and written in full (forward, not fd), one instruction per line, bodies of procedures,
+// repeats and ifs indented by 2 spaces; short repeats and ifs with simple bodies stay in one line:
+// repeat 4 [ forward 10 right 90 ].
+
+use super::block::{Block, CmpOp, Condition};
+use super::command::Command;
+use super::program::Arities;
+use crate::evaluator::eval::builtin_arities;
+use super::unit::{escape_word, parse_number, ArithOp, Unit};
+
+const INDENT: &str = "  ";
+const LINE_WIDTH: usize = 80; // longer repeats and ifs are split into lines
+
+struct Formatter {
+    arities: Arities, // procedures known to the parser: defined in the program and the known ones, by name
+    out: String,
+}
+
+fn arith(op: ArithOp) -> &'static str {
+    match op {
+        ArithOp::Add => "+",
+        ArithOp::Sub => "-",
+        ArithOp::Mul => "*",
+        ArithOp::Div => "/",
+    }
+}
+
+fn cmp(op: CmpOp) -> &'static str {
+    match op {
+        CmpOp::Less => "<",
+        CmpOp::Equal => "==",
+        CmpOp::Greater => ">",
+    }
+}
+
+fn word(w: &str) -> String {
+    format!("\"{}", escape_word(w))
+}
+
+// inside of a list: parentheses stay separate words, and words that look like numbers are escaped
+fn list_item(item: &Unit) -> String {
+    match item {
+        Unit::Word(w) if w == "(" || w == ")" => w.clone(),
+        Unit::Word(w) if w.is_empty() => "||".to_string(),
+        Unit::Word(w) if matches!(parse_number(w), Ok(("", _))) => format!("\\{}", w),
+        Unit::Word(w) => escape_word(w),
+        Unit::List(items) => list(items),
+        unit => unit_text(unit),
+    }
+}
+
+fn list(items: &[Unit]) -> String {
+    let items: Vec<String> = items.iter().map(list_item).collect();
+    format!("[{}]", items.join(" "))
+}
+
+// reporters are always called in parentheses, the parser doesn't have to know their inputs then
+fn call(name: &str, args: &[Unit]) -> String {
+    format!("({}{})", name, args_text(args))
+}
+
+// operand of an expression has to be simple, other units are put in parentheses
+fn operand(unit: &Unit) -> String {
+    match unit {
+        Unit::Val(_) | Unit::Var(_) | Unit::RepCount | Unit::RandomRange(..) | Unit::Call(..) => unit_text(unit),
+        unit => format!("({})", unit_text(unit)),
+    }
+}
+
+fn unit_text(unit: &Unit) -> String {
+    match unit {
+        Unit::Val(n) => n.to_string(),
+        Unit::Var(name) => format!(":{}", name),
+        Unit::RepCount => "repcount".to_string(),
+        Unit::Random(bound) => format!("random {}", unit_text(bound)),
+        Unit::RandomRange(low, high) => format!("(random{})", args_text([low.as_ref(), high.as_ref()])),
+        Unit::RandomFloat(bound) => format!("randomfloat {}", unit_text(bound)),
+        Unit::Exp(l, o, r) => format!("{} {} {}", operand(l), arith(*o), operand(r)),
+        Unit::Word(w) => word(w),
+        Unit::List(items) => list(items),
+        Unit::Call(name, args) => call(name, args),
+    }
+}
+
+// inputs in one line; negative number after another input stays a separate input (f 1 -2), see parse_operator
+fn args_text<'a>(args: impl IntoIterator<Item = &'a Unit>) -> String {
+    args.into_iter().map(|arg| format!(" {}", unit_text(arg))).collect()
+}
+
+fn condition(cond: &Condition) -> String {
+    match cond {
+        // predicate alone is parsed as comparison to true
+        Condition { left, operator: CmpOp::Equal, right: Unit::Word(w) } if w == "true" => unit_text(left),
+        Condition { left, operator, right } => format!("{} {} {}", unit_text(left), cmp(*operator), unit_text(right)),
+    }
+}
+
+fn command(c: &Command) -> String {
+    match c {
+        Command::Forward(u) => format!("forward {}", unit_text(u)),
+        Command::Back(u) => format!("back {}", unit_text(u)),
+        Command::Left(u) => format!("left {}", unit_text(u)),
+        Command::Right(u) => format!("right {}", unit_text(u)),
+        Command::Wait(u) => format!("wait {}", unit_text(u)),
+        Command::ClearScreen() => "clearscreen".to_string(),
+        Command::PenUp() => "penup".to_string(),
+        Command::PenDown() => "pendown".to_string(),
+        Command::SetColor(u) => format!("setcolor {}", unit_text(u)),
+        Command::SetColorPick(colors) => {
+            let colors: Vec<Unit> = colors.iter().map(|color| Unit::Word(color.clone())).collect();
+            format!("setcolor pick {}", list(&colors))
+        }
+        Command::SetTurtle(u) => format!("setturtle {}", unit_text(u)),
+        Command::Stop() => "stop".to_string(),
+        Command::Output(u) => format!("output {}", unit_text(u)),
+        Command::HideTurtle() => "hideturtle".to_string(),
+        Command::ShowTurtle() => "showturtle".to_string(),
+        Command::Window() => "window".to_string(),
+        Command::Rerandom(None) => "rerandom".to_string(),
+        Command::Rerandom(Some(u)) => format!("rerandom {}", unit_text(u)),
+        Command::Print(u) => format!("print {}", unit_text(u)),
+        Command::Show(u) => format!("show {}", unit_text(u)),
+        Command::Type(u) => format!("type {}", unit_text(u)),
+    }
+}
+
+impl Formatter {
+    // call without parentheses takes as many inputs as the procedure has, other calls are written in parentheses;
+    // call of procedure, that is unknown to the parser, takes everything up to the end of the line
+    fn call_block(&self, name: &str, args: &[Unit]) -> String {
+        match self.arities.get(name) {
+            Some(arity) if *arity != args.len() => call(name, args),
+            _ => format!("{}{}", name, args_text(args)),
+        }
+    }
+
+    // block that can be written in one line, with others after it
+    fn inline(&self, block: &Block) -> Option<String> {
+        match block {
+            Block::Single(c) => Some(command(c)),
+            Block::Call(name, args) if self.arities.contains_key(name) => Some(self.call_block(name, args)),
+            _ => None,
+        }
+    }
+
+    // body in one line, when it's short and has only commands and calls
+    fn inline_body(&self, head: &str, body: &[Block], indent: usize) -> Option<String> {
+        let blocks: Option<Vec<String>> = body.iter().map(|block| self.inline(block)).collect();
+        let line = format!("{} [ {} ]", head, blocks?.join(" "));
+        if indent * INDENT.len() + line.len() <= LINE_WIDTH && !body.is_empty() {
+            Some(line)
+        } else {
+            None
+        }
+    }
+
+    fn line(&mut self, text: &str, indent: usize) {
+        self.out.push_str(&INDENT.repeat(indent));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn bracketed(&mut self, head: &str, body: &[Block], indent: usize) {
+        match self.inline_body(head, body, indent) {
+            Some(line) => self.line(&line, indent),
+            None => {
+                self.line(&format!("{} [", head), indent);
+                self.blocks(body, indent + 1);
+                self.line("]", indent);
+            }
+        }
+    }
+
+    fn block(&mut self, block: &Block, indent: usize) {
+        match block {
+            Block::Single(c) => self.line(&command(c), indent),
+            Block::Repeat(u, body) => self.bracketed(&format!("repeat {}", unit_text(u)), body, indent),
+            Block::If(c, body) => self.bracketed(&format!("if {}", condition(c)), body, indent),
+            Block::Function(name, params, body) => {
+                let params: Vec<String> = params.iter().map(|param| format!(" :{}", param)).collect();
+                self.line(&format!("to {}{}", name, params.concat()), indent);
+                self.blocks(body, indent + 1);
+                self.line("end", indent);
+            }
+            Block::Call(name, args) => {
+                let text = self.call_block(name, args);
+                self.line(&text, indent);
+            }
+        }
+    }
+
+    fn blocks(&mut self, blocks: &[Block], indent: usize) {
+        for block in blocks {
+            self.block(block, indent);
+        }
+    }
+}
+
+fn definitions(blocks: &[Block], arities: &mut Arities) {
+    for block in blocks {
+        if let Block::Function(name, params, _) = block {
+            arities.entry(name.clone()).or_insert(params.len());
+        }
+        if let Block::Repeat(_, body) | Block::If(_, body) | Block::Function(_, _, body) = block {
+            definitions(body, arities);
+        }
+    }
+}
+
+// program in the canonical style; definitions at the top level are separated by empty lines;
+// built-in procedures are known, like in parse
+pub fn format(blocks: &[Block]) -> String {
+    format_with(blocks, &builtin_arities())
+}
+
+// same as format, for code parsed by parse_with with `known` procedures, eg by Interpreter::parse
+pub fn format_with(blocks: &[Block], known: &Arities) -> String {
+    let mut defined = Arities::new();
+    definitions(blocks, &mut defined);
+    let mut arities = known.clone();
+    arities.extend(defined);
+    let mut formatter = Formatter { arities, out: String::new() };
+
+    for (i, block) in blocks.iter().enumerate() {
+        let after_definition = i > 0 && matches!(blocks[i - 1], Block::Function(..));
+        if after_definition || (i > 0 && matches!(block, Block::Function(..))) {
+            formatter.out.push('\n');
+        }
+        formatter.block(block, 0);
+    }
+    formatter.out
+}
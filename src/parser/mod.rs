@@ -1,4 +1,5 @@
 pub mod unit;
 pub mod command;
 pub mod block;
-pub mod program;
\ No newline at end of file
+pub mod program;
+pub mod format;
\ No newline at end of file
@@ -4,9 +4,104 @@ use nom::{
     sequence::preceded,
     IResult,
 };
+use std::collections::HashMap;
 
 use super::block::{parse_block, Block};
+use crate::error::LogoError;
+use crate::evaluator::eval::builtin_arities;
 
-pub fn parse_program(input: &str) -> IResult<&str, Vec<Block>> {
-    many1(preceded(multispace0, parse_block))(input)
+// numbers of inputs of procedures, by name
+pub type Arities = HashMap<String, usize>;
+
+// headers of definitions (to name :a :b) in the code, found before parsing, so procedures can be called above them
+fn definitions(code: &str) -> Arities {
+    let mut arities = Arities::new();
+    let mut words = code.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        if !word.eq_ignore_ascii_case("to") {
+            continue;
+        }
+        if let Some(name) = words.next_if(|name| name.chars().all(|c| c.is_alphanumeric() || c == '_')) {
+            let mut params = 0;
+            while words.next_if(|param| param.starts_with(':')).is_some() {
+                params += 1;
+            }
+            arities.entry(name.to_lowercase()).or_insert(params);
+        }
+    }
+    arities
+}
+
+// line without its comment; ; escaped with \ or between vertical bars is a part of a word
+fn without_comment(line: &str) -> &str {
+    let mut in_bars = false;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '|' => in_bars = !in_bars,
+            ';' if !in_bars => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+// code as the grammar sees it: comments (from ; to the end of the line) are removed, lines ending with ~ are joined
+// with the next one, and CRLF line ends become LF (tabs are spaces for the grammar anyway);
+// joined lines are followed by empty ones, so lines in error messages stay the same
+pub fn clean(code: &str) -> String {
+    let mut cleaned = String::new();
+    let mut joined = 0;
+    for line in code.lines() {
+        let line = without_comment(line);
+        let escaped = line.trim_end().ends_with("\\~");
+        match line.trim_end().strip_suffix('~').filter(|_| !escaped) {
+            Some(continued) => {
+                cleaned.push_str(continued);
+                cleaned.push(' ');
+                joined += 1;
+            }
+            None => {
+                cleaned.push_str(line.trim_end());
+                cleaned.push_str(&"\n".repeat(joined + 1));
+                joined = 0;
+            }
+        }
+    }
+    cleaned
+}
+
+pub fn parse_program<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Vec<Block>> {
+    many1(preceded(multispace0, |i| parse_block(i, arities)))(input)
+}
+
+// parses whole program; unlike parse_program, input that can't be parsed is an error instead of being ignored;
+// built-in procedures are known, like in Interpreter::parse of a new interpreter
+pub fn parse(code: &str) -> Result<Vec<Block>, LogoError> {
+    parse_with(code, &builtin_arities())
+}
+
+// calls without parentheses take as many inputs as the procedure has (square 10 square 20 is two calls);
+// procedures defined in the code are known, and `known` are the other ones (defined earlier, primitives),
+// calls of procedures unknown to the parser take all units up to the end of the line
+pub fn parse_with(code: &str, known: &Arities) -> Result<Vec<Block>, LogoError> {
+    let code = &clean(code);
+    if code.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut arities = known.clone();
+    arities.extend(definitions(code));
+    match parse_program(code, &arities) {
+        Ok((rest, blocks)) if rest.trim().is_empty() => Ok(blocks),
+        Ok((rest, _)) => {
+            let line = code[..code.len() - rest.len()].lines().count().max(1);
+            let rest = rest.trim_start();
+            let snippet = rest.lines().next().unwrap_or(rest);
+            Err(LogoError::Parse(format!("unexpected input at line {}: {}", line, snippet)))
+        }
+        Err(e) => Err(LogoError::Parse(e.to_string())),
+    }
 }
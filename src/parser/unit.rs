@@ -1,91 +1,299 @@
 use nom::{
-    branch::alt, bytes::complete::{tag, tag_no_case, take_while1}, character::complete::{alphanumeric1, char, digit1, multispace0, space0, space1}, combinator::{map, map_res, opt}, multi::many1, sequence::{delimited, preceded, tuple}, IResult
+    branch::alt, bytes::complete::{tag, tag_no_case, take_while1}, character::complete::{char, digit1, multispace0, one_of, satisfy, space0, space1}, combinator::{map, map_res, not, opt, recognize, verify}, multi::{count, many0}, sequence::{delimited, preceded, terminated, tuple}, IResult
 };
 
+use super::program::Arities;
+
+// arithmetic operator of an expression
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArithOp {
+    Add, // +
+    Sub, // -
+    Mul, // *
+    Div, // /
+}
+
+impl ArithOp {
+    pub fn apply(self, l: f64, r: f64) -> f64 {
+        match self {
+            ArithOp::Add => l + r,
+            ArithOp::Sub => l - r,
+            ArithOp::Mul => l * r,
+            ArithOp::Div => l / r,
+        }
+    }
+
+    // name of the operation in logo, used in error messages
+    pub fn name(self) -> &'static str {
+        match self {
+            ArithOp::Add => "sum",
+            ArithOp::Sub => "difference",
+            ArithOp::Mul => "product",
+            ArithOp::Div => "quotient",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Unit {
     Val(f64),  // constant value - number (integer or float)
     Var(String), // variable name
-    Random(Box<Unit>), // random value
-    Exp(Box<Unit>, String, Box<Unit>), // expression, eg :size / 3
-    Pick(Vec<String>)
+    RepCount, // iteration of the innermost repeat
+    Random(Box<Unit>), // random integer from 0 to n - 1
+    RandomRange(Box<Unit>, Box<Unit>), // random integer from lo to hi (both included), (random lo hi)
+    RandomFloat(Box<Unit>), // random float from 0 to n (n excluded)
+    Exp(Box<Unit>, ArithOp, Box<Unit>), // expression, eg :size / 3
+    Word(String), // quoted word, eg "red
+    List(Vec<Unit>), // list literal, eg [1 2 [red blue]]; contains only numbers (Val), words and lists
+    Call(String, Vec<Unit>), // procedure that outputs a value, in parentheses, eg (sensor 1)
 }
 
+// letters of procedure and variable names
+pub fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
 
-fn parse_random(input: &str) -> IResult<&str, Unit> {
-    let (input, _) = alt((tag_no_case("random"), tag_no_case("rm")))(input)?;
+// keyword, that is a whole word: `sp` is stop, but `spiral` is a name
+pub fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    terminated(tag_no_case(word), not(satisfy(is_name_char)))
+}
+
+fn parse_random<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    let (input, _) = alt((keyword("random"), keyword("rm")))(input)?;
     let (input, _) = space1(input)?;
-    let (input, value) = parse_unit(input)?;
+    let (input, value) = parse_unit(input, arities)?;
     Ok((input, Unit::Random(Box::new(value))))
 }
 
+// like in UCBLogo, random with two arguments has to be in parentheses: (random 1 6)
+fn parse_random_range<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    let (input, _) = char('(')(input)?;
+    let (input, _) = space0(input)?;
+    let (input, _) = alt((keyword("random"), keyword("rm")))(input)?;
+    let (input, low) = preceded(space1, |i| parse_unit(i, arities))(input)?;
+    let (input, high) = opt(preceded(space1, |i| parse_unit(i, arities)))(input)?;
+    let (input, _) = preceded(space0, char(')'))(input)?;
+    match high {
+        Some(high) => Ok((input, Unit::RandomRange(Box::new(low), Box::new(high)))),
+        None => Ok((input, Unit::Random(Box::new(low)))),
+    }
+}
 
-fn parse_pick(input: &str) -> IResult<&str, Unit> {
-    let (input, _) = tag_no_case("pick")(input)?;
+fn parse_random_float<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    let (input, _) = keyword("randomfloat")(input)?;
     let (input, _) = space1(input)?;
-    let (input, list) = delimited(
-        char('['), 
-        many1(map(preceded(multispace0, alphanumeric1), |s: &str| s.to_string())), // Zbierz elementy jako stringi
+    let (input, value) = parse_unit(input, arities)?;
+    Ok((input, Unit::RandomFloat(Box::new(value))))
+}
+
+
+// word ends at whitespace, bracket or parenthesis
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !"[]()".contains(c)
+}
+
+fn word_error(input: &str) -> nom::Err<nom::error::Error<&str>> {
+    nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Escaped))
+}
+
+// text of a word (can be empty): \ takes the next character as it is, and so does everything between vertical bars,
+// so "hello\ world, "|hello world| and "hello| |world are the same word
+fn word_text(input: &str) -> IResult<&str, String> {
+    let mut text = String::new();
+    let mut chars = input.char_indices();
+    let mut end = input.len();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped)) => text.push(escaped),
+                None => return Err(word_error(&input[i..])),
+            },
+            '|' => loop {
+                match chars.next() {
+                    Some((_, '|')) => break,
+                    Some((_, c)) => text.push(c),
+                    None => return Err(word_error(&input[i..])), // bar is not closed
+                }
+            },
+            c if is_word_char(c) => text.push(c),
+            _ => {
+                end = i;
+                break;
+            }
+        }
+    }
+    Ok((&input[end..], text))
+}
+
+// word written so that word_text reads it back: special characters are escaped with \\
+pub fn escape_word(word: &str) -> String {
+    word.chars().map(|c| match c {
+        c if !is_word_char(c) || "|;\\~".contains(c) => format!("\\{}", c),
+        c => c.to_string(),
+    }).collect()
+}
+
+// quoted word, eg "red, "|light blue|, "a\[1\]
+pub fn parse_word(input: &str) -> IResult<&str, Unit> {
+    let (input, _) = char('"')(input)?;
+    let (input, word) = word_text(input)?;
+    Ok((input, Unit::Word(word)))
+}
+
+// inside of list literal nothing is evaluated: [:x "a 1] is list of words ":x and "a, and number 1;
+// parentheses are separate words, so instruction lists like [fd (first :points)] can be run;
+// words are read like after a quote, so [|light blue| red\ wine] has two words
+fn parse_list_item(input: &str) -> IResult<&str, Unit> {
+
+    fn parse_item_word(input: &str) -> IResult<&str, Unit> {
+        let (rest, word) = word_text(input)?;
+        let written = &input[..input.len() - rest.len()];
+        if written.is_empty() {
+            return Err(word_error(input));
+        }
+        // number only when written without escapes: |10| is a word
+        match parse_number(written) {
+            Ok(("", number)) if written == word => Ok((rest, number)),
+            _ => Ok((rest, Unit::Word(word))),
+        }
+    }
+
+    alt((
+        parse_list,
+        map(alt((tag("("), tag(")"))), |paren: &str| Unit::Word(paren.to_string())),
+        parse_item_word,
+    ))(input)
+}
+
+pub fn parse_list(input: &str) -> IResult<&str, Unit> {
+    let (input, items) = delimited(
+        char('['),
+        many0(preceded(multispace0, parse_list_item)),
         preceded(multispace0, char(']')),
     )(input)?;
-    Ok((input, Unit::Pick(list)))
+    Ok((input, Unit::List(items)))
 }
 
+// integer or float with optional negative sign and exponent: 67, -67.96, 1e300, 2.5E-3;
+// number has to end the word, so 1e or 10abc are errors, not a number and a name
 pub fn parse_number(input: &str) -> IResult<&str, Unit> {
-    alt((
-        // parse float with optional negative sign
-        map_res(
-            tuple((
-                opt(char('-')), // Optional negative sign
+    map_res(
+        terminated(
+            recognize(tuple((
+                opt(char('-')),
                 digit1,
-                char('.'),
-                digit1,
-            )),
-            |(sign, integer, _, fraction): (Option<char>, &str, char, &str)| {
-                let mut value: f64 = format!("{}.{}", integer, fraction).parse().unwrap();
-                if sign.is_some() {
-                    value = -value;
-                }
-                Ok::<_, nom::Err<(&str, nom::error::ErrorKind)>>(Unit::Val(value))
-            },
+                opt(tuple((char('.'), digit1))),
+                opt(tuple((one_of("eE"), opt(one_of("+-")), digit1))),
+            ))),
+            not(satisfy(is_name_char)),
         ),
-        // parse integer with optional negative sign
-        map_res(
-            tuple((opt(char('-')), digit1)),
-            |(sign, digits): (Option<char>, &str)| {
-                let mut value: f64 = digits.parse().unwrap();
-                if sign.is_some() {
-                    value = -value;
-                }
-                Ok::<_, nom::Err<(&str, nom::error::ErrorKind)>>(Unit::Val(value))
-            },
-        ),
-    ))(input)
+        |number: &str| match number.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Unit::Val(value)),
+            _ => Err("Number out of range"),
+        },
+    )(input)
 }
 
 
+fn parse_repcount(input: &str) -> IResult<&str, Unit> {
+    let (input, _) = alt((keyword("repcount"), tag("#")))(input)?;
+    Ok((input, Unit::RepCount))
+}
+
 fn parse_variable(input: &str) -> IResult<&str, Unit> {
     let (input, _) = nom::bytes::complete::tag(":")(input)?;
-    map(take_while1(|c: char| c.is_alphanumeric() || c == '_'), |s: &str| {
+    map(take_while1(is_name_char), |s: &str| {
         Unit::Var(s.to_string())
     })(input)
 }
 
-// todo: obsługa zagnieżdżonych wyrażeń
-fn parse_expression(input: &str) -> IResult<&str, Unit> {
-    let (input, (left, _, operator, _, right)) = tuple((
-        alt((parse_number, parse_variable)),
-        space0,
-        alt((tag("+"), tag("-"), tag("*"), tag("/"))),
+// in parentheses, the call takes all units up to `)`: (list 1 2 3), (sum :a)
+pub fn parse_call<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    let (input, _) = char('(')(input)?;
+    let (input, _) = space0(input)?;
+    let (input, name) = verify(take_while1(is_name_char), |name: &str| !name.starts_with(|c: char| c.is_ascii_digit()))(input)?;
+    let (input, args) = many0(preceded(space1, |i| parse_unit(i, arities)))(input)?;
+    let (input, _) = preceded(space0, char(')'))(input)?;
+    Ok((input, Unit::Call(name.to_lowercase(), args)))
+}
+
+// without parentheses, only procedures known to the parser (see parser/program) can be called,
+// with as many inputs as they take: fd double 5, if emptyp :points [ stop ]
+fn parse_bare_call<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    let (input, name) = map(take_while1(is_name_char), str::to_lowercase)(input)?;
+    let n = match arities.get(&name) {
+        Some(n) => *n,
+        None => return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify))),
+    };
+    let (input, args) = count(preceded(space1, |i| parse_unit(i, arities)), n)(input)?;
+    Ok((input, Unit::Call(name, args)))
+}
+
+// unit in parentheses, so expressions can be nested: (:a + 1) * 2
+fn parse_group<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    delimited(
+        tuple((char('('), space0)),
+        |i| parse_unit(i, arities),
+        tuple((space0, char(')'))),
+    )(input)
+}
+
+// calls of known procedures don't need parentheses in expressions: 2 * double 5
+fn operand<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    alt((
+        parse_number,
+        parse_variable,
+        parse_repcount,
+        |i| parse_random_range(i, arities),
+        |i| parse_group(i, arities),
+        |i| parse_call(i, arities),
+        |i| parse_bare_call(i, arities),
+    ))(input)
+}
+
+// minus with a space before it and a digit right after it starts a negative number, like in Logo:
+// f 1 -2 is a call with two inputs, f 1 - 2 and f 1-2 with one
+fn parse_operator(input: &str) -> IResult<&str, ArithOp> {
+    let (rest, spaces) = space0(input)?;
+    let (rest, operator) = alt((
+        map(char('+'), |_| ArithOp::Add),
+        map(char('-'), |_| ArithOp::Sub),
+        map(char('*'), |_| ArithOp::Mul),
+        map(char('/'), |_| ArithOp::Div),
+    ))(rest)?;
+    if operator == ArithOp::Sub && !spaces.is_empty() && rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify)));
+    }
+    Ok((rest, operator))
+}
+
+// todo: obsługa zagnieżdżonych wyrażeń bez nawiasów
+fn parse_expression<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    let (input, (left, operator, _, right)) = tuple((
+        |i| operand(i, arities),
+        parse_operator,
         space0,
-        alt((parse_number, parse_variable)),
+        |i| operand(i, arities),
     ))(input)?;
     Ok((
         input,
-        Unit::Exp(Box::new(left), operator.to_string(), Box::new(right)),
+        Unit::Exp(Box::new(left), operator, Box::new(right)),
     ))
 }
 
-pub fn parse_unit(input: &str) -> IResult<&str, Unit> {
-    alt((parse_expression, parse_number, parse_variable, parse_random))(input)
+pub fn parse_unit<'a>(input: &'a str, arities: &Arities) -> IResult<&'a str, Unit> {
+    alt((
+        |i| parse_expression(i, arities),
+        parse_number,
+        parse_variable,
+        parse_repcount,
+        |i| parse_random_float(i, arities),
+        |i| parse_random(i, arities),
+        |i| parse_random_range(i, arities),
+        |i| parse_group(i, arities),
+        |i| parse_call(i, arities),
+        |i| parse_bare_call(i, arities),
+        parse_word,
+        parse_list,
+    ))(input)
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
 use lista7::parser::{command::Command, unit::Unit};
-use lista7::parser::block::{Block, Condition};
+use lista7::parser::block::{Block, CmpOp, Condition};
 use lista7::evaluator::{environment::Env, turtle::Turtle, eval::eval_block};
+use lista7::evaluator::value::Value;
+use lista7::LogoError;
 
 fn setup_env() -> Env {
     let mut env = Env::new();
-    env.set_var("x".to_string(), 100.0);
+    env.set_var("x".to_string(), Value::Number(100.0));
     env
 }
 
@@ -14,7 +16,7 @@ fn test_single_block() {
     let mut env = setup_env();
     let block = Block::Single(Command::Forward(Unit::Val(50.0)));
 
-    eval_block(&block, &mut turtle, &mut env);
+    eval_block(&block, &mut turtle, &mut env).unwrap();
 
     assert_eq!(turtle.y, 250.0); 
     assert_eq!(turtle.lines.len(), 1);
@@ -27,12 +29,12 @@ fn test_if_block() {
     let condition = Condition {
         left: Unit::Val(50.0),
         right: Unit::Val(100.0),
-        operator: "<".to_string(),
+        operator: CmpOp::Less,
     };
     let instructions = vec![Block::Single(Command::Forward(Unit::Val(50.0)))];
     let block = Block::If(condition, instructions);
 
-    eval_block(&block, &mut turtle, &mut env);
+    eval_block(&block, &mut turtle, &mut env).unwrap();
 
     assert_eq!(turtle.y, 250.0);
     assert_eq!(turtle.lines.len(), 1);
@@ -45,7 +47,7 @@ fn test_repeat_block() {
     let instructions = vec![Block::Single(Command::Forward(Unit::Val(10.0)))];
     let block = Block::Repeat(Unit::Val(5.0), instructions);
 
-    eval_block(&block, &mut turtle, &mut env);
+    eval_block(&block, &mut turtle, &mut env).unwrap();
 
     assert_eq!(turtle.y, 250.0);
     assert_eq!(turtle.lines.len(), 5);
@@ -62,14 +64,14 @@ fn test_function_and_call_block() {
         vec!["distance".to_string()],
         vec![Block::Single(Command::Forward(Unit::Var("distance".to_string())))],
     );
-    eval_block(&function, &mut turtle, &mut env);
+    eval_block(&function, &mut turtle, &mut env).unwrap();
 
     // call
     let call = Block::Call(
         "draw_line".to_string(),
         vec![Unit::Val(100.0)],
     );
-    eval_block(&call, &mut turtle, &mut env);
+    eval_block(&call, &mut turtle, &mut env).unwrap();
 
     assert_eq!(turtle.y, 200.0);
     assert_eq!(turtle.lines.len(), 1);
@@ -94,10 +96,10 @@ fn test_eval_block_function_no_args() {
         ],
     );
 
-    eval_block(&func_block, &mut turtle, &mut env);
+    eval_block(&func_block, &mut turtle, &mut env).unwrap();
 
     let call_block = Block::Call("draw_square".to_string(), vec![]);
-    eval_block(&call_block, &mut turtle, &mut env);
+    eval_block(&call_block, &mut turtle, &mut env).unwrap();
 
     assert_eq!(turtle.x, 400.0); 
     assert_eq!(turtle.y, 300.0);
@@ -112,12 +114,12 @@ fn test_if_block_false_condition() {
     let condition = Condition {
         left: Unit::Val(150.0),
         right: Unit::Val(100.0),
-        operator: "<".to_string(),
+        operator: CmpOp::Less,
     };
     let instructions = vec![Block::Single(Command::Forward(Unit::Val(50.0)))];
     let block = Block::If(condition, instructions);
 
-    eval_block(&block, &mut turtle, &mut env);
+    eval_block(&block, &mut turtle, &mut env).unwrap();
 
     assert_eq!(turtle.x, 400.0); 
     assert_eq!(turtle.y, 300.0); 
@@ -134,15 +136,27 @@ fn test_call_block_wrong_arguments() {
         vec!["distance".to_string()],
         vec![Block::Single(Command::Forward(Unit::Var("distance".to_string())))],
     );
-    eval_block(&function, &mut turtle, &mut env);
+    eval_block(&function, &mut turtle, &mut env).unwrap();
 
     let call = Block::Call("draw_line".to_string(), vec![]);
-    eval_block(&call, &mut turtle, &mut env);
+    assert_eq!(
+        eval_block(&call, &mut turtle, &mut env),
+        Err(LogoError::WrongArity { name: "draw_line".to_string(), expected: 1, got: 0 })
+    );
 
     assert_eq!(turtle.y, 300.0); 
     assert_eq!(turtle.lines.len(), 0);
 }
 
+#[test]
+fn test_call_block_unknown_function() {
+    let mut turtle = Turtle::new(800.0, 600.0);
+    let mut env = setup_env();
 
+    let call = Block::Call("nothing".to_string(), vec![]);
 
-
+    assert_eq!(
+        eval_block(&call, &mut turtle, &mut env),
+        Err(LogoError::UnknownProcedure("nothing".to_string()))
+    );
+}
@@ -1,40 +1,41 @@
 use lista7::parser::unit::Unit;
 use lista7::parser::command::Command;
-use lista7::parser::block::{Block, Condition, parse_block, parse_condition};
+use lista7::parser::block::{Block, CmpOp, Condition, parse_block, parse_condition};
+use lista7::parser::program::Arities;
 
 #[test]
 fn test_parse_condition() {
     assert_eq!(
-        parse_condition(":size < 5"),
+        parse_condition(":size < 5", &Arities::new()),
         Ok((
             "",
             Condition {
                 left: Unit::Var("size".to_string()),
-                operator: "<".to_string(),
+                operator: CmpOp::Less,
                 right: Unit::Val(5.0),
             }
         ))
     );
 
     assert_eq!(
-        parse_condition(":x == :y"),
+        parse_condition(":x == :y", &Arities::new()),
         Ok((
             "",
             Condition {
                 left: Unit::Var("x".to_string()),
-                operator: "==".to_string(),
+                operator: CmpOp::Equal,
                 right: Unit::Var("y".to_string()),
             }
         ))
     );
 
     assert_eq!(
-        parse_condition("-10 > :value"),
+        parse_condition("-10 > :value", &Arities::new()),
         Ok((
             "",
             Condition {
                 left: Unit::Val(-10.0),
-                operator: ">".to_string(),
+                operator: CmpOp::Greater,
                 right: Unit::Var("value".to_string()),
             }
         ))
@@ -45,7 +46,7 @@ fn test_parse_condition() {
 fn test_parse_repeat() {
     let input = "repeat 5 [ fd -100 rt 144 ]";
     assert_eq!(
-        parse_block(input),
+        parse_block(input, &Arities::new()),
         Ok((
             "",
             Block::Repeat(
@@ -63,13 +64,13 @@ fn test_parse_repeat() {
 fn test_parse_if() {
     let input = "if :size > 5 [ fd 100 rt 144 ]";
     assert_eq!(
-        parse_block(input),
+        parse_block(input, &Arities::new()),
         Ok((
             "",
             Block::If(
                 Condition {
                     left: Unit::Var("size".to_string()),
-                    operator: ">".to_string(),
+                    operator: CmpOp::Greater,
                     right: Unit::Val(5.0),
                 },
                 vec![
@@ -85,13 +86,13 @@ fn test_parse_if() {
 fn test_nested_expressions() {
     let input = "if :size > 5 [ repeat 5 [ fd 100 rt -144 ] ]";
     assert_eq!(
-        parse_block(input),
+        parse_block(input, &Arities::new()),
         Ok((
             "",
             Block::If(
                 Condition {
                     left: Unit::Var("size".to_string()),
-                    operator: ">".to_string(),
+                    operator: CmpOp::Greater,
                     right: Unit::Val(5.0),
                 },
                 vec![
@@ -112,7 +113,7 @@ fn test_nested_expressions() {
 fn test_parse_function_no_args() {
     let input = "to star repeat 5 [ penup fd 100 rt 144 pendown ] end";
     assert_eq!(
-        parse_block(input),
+        parse_block(input, &Arities::new()),
         Ok((
             "",
             Block::Function(
@@ -137,7 +138,7 @@ fn test_parse_function_no_args() {
 #[test]fn test_parse_function_with_args() {
     let input = "to star :arg1 :arg2 repeat :size [ clearscreen rt 144 ] end";
     assert_eq!(
-        parse_block(input),
+        parse_block(input, &Arities::new()),
         Ok((
             "",
             Block::Function(
@@ -161,7 +162,7 @@ fn test_parse_function_no_args() {
 #[test]fn test_parse_function_call_no_args() {
     let input = "star";
     assert_eq!(
-        parse_block(input),
+        parse_block(input, &Arities::new()),
         Ok((
             "",
             Block::Call(
@@ -175,7 +176,7 @@ fn test_parse_function_no_args() {
 #[test]fn test_parse_function_call_args() {
     let input = "star 30 :size";
     assert_eq!(
-        parse_block(input),
+        parse_block(input, &Arities::new()),
         Ok((
             "",
             Block::Call(
@@ -186,3 +187,28 @@ fn test_parse_function_no_args() {
 }
 
 
+
+#[test]
+fn test_parse_if_bare_predicate() {
+    let arities = Arities::from([("emptyp".to_string(), 1)]);
+    let predicate = |arg: Unit| Condition {
+        left: Unit::Call("emptyp".to_string(), vec![arg]),
+        operator: CmpOp::Equal,
+        right: Unit::Word("true".to_string()),
+    };
+    let cases = vec![
+        ("if emptyp [] [ fd 1 ]", predicate(Unit::List(vec![]))),
+        ("if emptyp :points [ fd 1 ]", predicate(Unit::Var("points".to_string()))),
+        ("if (emptyp []) [ fd 1 ]", predicate(Unit::List(vec![]))),
+    ];
+
+    for (input, condition) in cases {
+        match parse_block(input, &arities) {
+            Ok((rest, block)) => {
+                assert_eq!(rest, "");
+                assert_eq!(block, Block::If(condition, vec![Block::Single(Command::Forward(Unit::Val(1.0)))]));
+            }
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use lista7::parser::block::Block;
+use lista7::parser::command::Command;
+use lista7::parser::program::{parse, parse_with, Arities};
+use lista7::parser::unit::{ArithOp, Unit};
+use lista7::{Config, Interpreter, LogoError};
+
+fn val(n: f64) -> Unit {
+    Unit::Val(n)
+}
+
+fn var(name: &str) -> Unit {
+    Unit::Var(name.to_string())
+}
+
+fn call(name: &str, args: Vec<Unit>) -> Block {
+    Block::Call(name.to_string(), args)
+}
+
+// blocks after the definitions
+fn parse_calls(input: &str) -> Vec<Block> {
+    match parse(input) {
+        Ok(blocks) => blocks.into_iter().filter(|block| !matches!(block, Block::Function(..))).collect(),
+        Err(_) => panic!("Błąd parsowania dla '{}'", input),
+    }
+}
+
+#[test]
+fn test_several_calls_in_line() {
+    let square = "to square :size\n repeat 4 [ fd :size rt 90 ]\nend\n";
+    assert_eq!(
+        parse_calls(&format!("{}square 10 square 20 fd 5", square)),
+        vec![call("square", vec![val(10.0)]), call("square", vec![val(20.0)]), Block::Single(Command::Forward(val(5.0)))]
+    );
+    // called above the definition
+    assert_eq!(
+        parse_calls(&format!("square :a * 2 square 1\n{}", square)),
+        vec![call("square", vec![Unit::Exp(Box::new(var("a")), ArithOp::Mul, Box::new(val(2.0)))]), call("square", vec![val(1.0)])]
+    );
+    // procedure without parameters
+    assert_eq!(
+        parse_calls("to star\n repeat 5 [ fd 100 rt 144 ]\nend\nstar star"),
+        vec![call("star", vec![]), call("star", vec![])]
+    );
+}
+
+#[test]
+fn test_recursive_call_between_commands() {
+    let program = "to fern :size :sign\n if :size < 1 [ stop ]\n rt 70 * :sign fern :size * 0.5 :sign * -1 lt 70 * :sign\nend";
+    let body = match parse(program) {
+        Ok(blocks) => match &blocks[0] {
+            Block::Function(_, _, body) => body.clone(),
+            _ => panic!("Błąd parsowania dla '{}'", program),
+        },
+        Err(_) => panic!("Błąd parsowania dla '{}'", program),
+    };
+
+    let sign = || Box::new(var("sign"));
+    assert_eq!(
+        body[1..],
+        vec![
+            Block::Single(Command::Right(Unit::Exp(Box::new(val(70.0)), ArithOp::Mul, sign()))),
+            call("fern", vec![
+                Unit::Exp(Box::new(var("size")), ArithOp::Mul, Box::new(val(0.5))),
+                Unit::Exp(sign(), ArithOp::Mul, Box::new(val(-1.0))),
+            ]),
+            Block::Single(Command::Left(Unit::Exp(Box::new(val(70.0)), ArithOp::Mul, sign()))),
+        ]
+    );
+}
+
+#[test]
+fn test_reporter_without_parentheses() {
+    let double = "to double :x\n output :x * 2\nend\n";
+    let double_of = |arg| Unit::Call("double".to_string(), vec![arg]);
+
+    assert_eq!(parse_calls(&format!("{}fd double 5", double)), vec![Block::Single(Command::Forward(double_of(val(5.0))))]);
+    assert_eq!(
+        parse_calls(&format!("{}fd double double 5 rt 90", double)),
+        vec![Block::Single(Command::Forward(double_of(double_of(val(5.0))))), Block::Single(Command::Right(val(90.0)))]
+    );
+    // inputs are whole expressions, like in logo: double 2 + 3 is double 5
+    assert_eq!(
+        parse_calls(&format!("{}fd double 2 + 3", double)),
+        vec![Block::Single(Command::Forward(double_of(Unit::Exp(Box::new(val(2.0)), ArithOp::Add, Box::new(val(3.0))))))]
+    );
+}
+
+#[test]
+fn test_calls_in_parentheses() {
+    let program = "to f :a :b\n fd :a\nend\n(f 1 2 3)\n(f)\n(f 1 (f 2 3))";
+    assert_eq!(
+        parse_calls(program),
+        vec![
+            call("f", vec![val(1.0), val(2.0), val(3.0)]),
+            call("f", vec![]),
+            call("f", vec![val(1.0), Unit::Call("f".to_string(), vec![val(2.0), val(3.0)])]),
+        ]
+    );
+}
+
+#[test]
+fn test_keyword_prefix_is_a_name() {
+    let program = "to spiral :n\n fd :n\nend\nto fdx :n\n bk :n\nend\nto stopper :n\n rt :n\nend\nspiral 5 fdx 1 stopper 2 sp";
+    assert_eq!(
+        parse_calls(program),
+        vec![call("spiral", vec![val(5.0)]), call("fdx", vec![val(1.0)]), call("stopper", vec![val(2.0)]), Block::Single(Command::Stop())]
+    );
+}
+
+#[test]
+fn test_known_procedures() {
+    let mut known = Arities::new();
+    known.insert("side".to_string(), 1);
+    known.insert("dot".to_string(), 0);
+    assert_eq!(
+        parse_with("side 1 dot side 2", &known),
+        Ok(vec![call("side", vec![val(1.0)]), call("dot", vec![]), call("side", vec![val(2.0)])])
+    );
+
+    // extra input is not taken by the call
+    assert!(matches!(parse_with("side 1 2", &known), Err(LogoError::Parse(_))));
+    // not enough inputs: the call takes what there is, and fails when it runs
+    assert_eq!(parse_with("side", &known), Ok(vec![call("side", vec![])]));
+    // procedure unknown to the parser takes everything up to the end of the line
+    assert_eq!(parse("other 1 2\nfd 3").unwrap()[0], call("other", vec![val(1.0), val(2.0)]));
+}
+
+#[test]
+fn test_interpreter_knows_procedures() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.register_reporter("twice", 1, |args| Ok(args[0] * 2.0));
+    interpreter.run_str("to square :size\n repeat 4 [ fd :size rt 90 ]\nend").unwrap();
+
+    interpreter.run_str("square 10 square twice 10\nprint first [a b] print count se [1 2] [3]").unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 8);
+    assert_eq!(interpreter.output(), "a\n3\n");
+
+    // also in lists given to run
+    interpreter.run_str("run [square 1 square 2] print (run [twice 4])").unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 16);
+    assert_eq!(interpreter.take_output(), "a\n3\n8\n");
+}
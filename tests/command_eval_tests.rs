@@ -1,28 +1,31 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use lista7::evaluator::eval::eval_command;
-use lista7::evaluator::{environment::Env, turtle::Turtle};
+use lista7::evaluator::{environment::Env, turtle::Turtle, value::Value};
 use lista7::parser::{unit::Unit, command::Command};
+use lista7::LogoError;
 
 #[test]
 fn test_left_rotation() {
     let mut turtle = Turtle::new(100.0, 100.0);
-    let env = Env::new(); 
-    eval_command(Command::Right(Unit::Val(90.0)), &mut turtle, env);
+    let mut env = Env::new(); 
+    eval_command(&Command::Right(Unit::Val(90.0)), &mut turtle, &mut env).unwrap();
     assert_eq!(turtle.angle, 360.0); 
 }
 
 #[test]
 fn test_right_rotation() {
     let mut turtle = Turtle::new(100.0, 100.0);
-    let env = Env::new();
-    eval_command(Command::Left(Unit::Val(90.0)), &mut turtle, env);
+    let mut env = Env::new();
+    eval_command(&Command::Left(Unit::Val(90.0)), &mut turtle, &mut env).unwrap();
     assert_eq!(turtle.angle, 180.0);
 }
 
 #[test]
 fn test_forward_movement() {
     let mut turtle = Turtle::new(100.0, 100.0);
-    let env = Env::new();
-    eval_command(Command::Forward(Unit::Val(50.0)), &mut turtle, env);
+    let mut env = Env::new();
+    eval_command(&Command::Forward(Unit::Val(50.0)), &mut turtle, &mut env).unwrap();
     let expected_y = 50.0 - 50.0 * 1.0;  
     assert_eq!(turtle.y, expected_y);
 }
@@ -30,17 +33,38 @@ fn test_forward_movement() {
 #[test]
 fn test_pen_up() {
     let mut turtle = Turtle::new(100.0, 100.0);
-    let env = Env::new();
-    eval_command(Command::PenUp(), &mut turtle, env);
+    let mut env = Env::new();
+    eval_command(&Command::PenUp(), &mut turtle, &mut env).unwrap();
     assert_eq!(turtle.pen_down, false);
 }
 
 #[test]
 fn test_pen_down() {
     let mut turtle = Turtle::new(100.0, 100.0);
-    let env = Env::new();
+    let mut env = Env::new();
     assert_eq!(turtle.pen_down, true);
-    eval_command(Command::PenUp(), &mut turtle, env);
+    eval_command(&Command::PenUp(), &mut turtle, &mut env).unwrap();
     assert_eq!(turtle.pen_down, false);
 }
 
+
+#[test]
+fn test_wait_and_set_turtle() {
+    let mut turtle = Turtle::new(100.0, 100.0);
+    let mut env = Env::new();
+    eval_command(&Command::Wait(Unit::Val(3.0)), &mut turtle, &mut env).unwrap();
+    eval_command(&Command::SetTurtle(Unit::Val(1.0)), &mut turtle, &mut env).unwrap();
+    assert_eq!(turtle.time, 3.0);
+    assert_eq!(turtle.id, 1);
+}
+
+#[test]
+fn test_set_turtle_bad_id() {
+    let mut turtle = Turtle::new(100.0, 100.0);
+    let mut env = Env::new();
+    for id in [-1.0, 1.7, 1e10] {
+        let result = eval_command(&Command::SetTurtle(Unit::Val(id)), &mut turtle, &mut env);
+        assert_eq!(result, Err(LogoError::BadInput { name: "setturtle".to_string(), value: Value::Number(id) }));
+    }
+    assert_eq!(turtle.id, 0);
+}
@@ -1,5 +1,6 @@
-use lista7::parser::unit::Unit;
+use lista7::parser::unit::{ArithOp, Unit};
 use lista7::parser::command::{Command, parse_command};
+use lista7::parser::program::Arities;
 
 #[test]
 fn test_parse_forward() {
@@ -11,7 +12,7 @@ fn test_parse_forward() {
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_command(input);
+        let result = parse_command(input, &Arities::new());
         match result {
             Ok((_, command)) => assert_eq!(command, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
@@ -24,30 +25,30 @@ fn test_parse_commands() {
     let test_cases = vec![
         ("lt 100", Command::Left(Unit::Val(100.0))),
         ("rt :size", Command::Right(Unit::Var("size".to_string()))),
-        ("setcolor \"blue", Command::SetColor("blue".to_string())),
+        ("setcolor \"blue", Command::SetColor(Unit::Word("blue".to_string()))),
         ("rIgHt :size * 3", 
             Command::Right(
                 Unit::Exp(
                     Box::new(Unit::Var("size".to_string())),
-                    "*".to_string(),
+                    ArithOp::Mul,
                     Box::new(Unit::Val(3.0))))),
         ("BACK :size / :times", 
             Command::Back(
                 Unit::Exp(
                     Box::new(Unit::Var("size".to_string())),
-                    "/".to_string(),
+                    ArithOp::Div,
                     Box::new(Unit::Var("times".to_string()))))),
         ("wait 20 + 4.9", 
             Command::Wait(
                 Unit::Exp(
                     Box::new(Unit::Val(20.0)),
-                    "+".to_string(),
+                    ArithOp::Add,
                     Box::new(Unit::Val(4.9))))),
         ("setturtle 3", Command::SetTurtle(Unit::Val(3.0)))
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_command(input);
+        let result = parse_command(input, &Arities::new());
         match result {
             Ok((_, command)) => assert_eq!(command, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
@@ -68,7 +69,7 @@ fn test_parse_no_args() {
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_command(input);
+        let result = parse_command(input, &Arities::new());
         match result {
             Ok((_, command)) => assert_eq!(command, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
@@ -84,7 +85,46 @@ fn test_parse_setcolors_with_pick() {
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_command(input);
+        let result = parse_command(input, &Arities::new());
+        match result {
+            Ok((_, command)) => assert_eq!(command, expected),
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_setcolor_pick_list_literal() {
+    let colors = |names: &[&str]| Command::SetColorPick(names.iter().map(|e| e.to_string()).collect());
+    let test_cases = vec![
+        ("setcolor pick [red\n  blue]", colors(&["red", "blue"])),
+        ("sc pick [|red| \\red]", colors(&["red", "red"])),
+        ("SetColor Pick [red]", colors(&["red"])),
+    ];
+
+    for (input, expected) in test_cases {
+        match parse_command(input, &Arities::new()) {
+            Ok(("", command)) => assert_eq!(command, expected),
+            _ => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+
+    // only words can be colors
+    for input in ["setcolor pick []", "setcolor pick [red [blue]]", "setcolor pick [red 1]"] {
+        assert!(!matches!(parse_command(input, &Arities::new()), Ok((_, Command::SetColorPick(_)))), "{}", input);
+    }
+}
+
+#[test]
+fn test_parse_rerandom() {
+    let test_cases = vec![
+        ("rerandom", Command::Rerandom(None)),
+        ("rerandom 42", Command::Rerandom(Some(Unit::Val(42.0)))),
+        ("RERANDOM :seed", Command::Rerandom(Some(Unit::Var("seed".to_string())))),
+    ];
+
+    for (input, expected) in test_cases {
+        let result = parse_command(input, &Arities::new());
         match result {
             Ok((_, command)) => assert_eq!(command, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
@@ -97,7 +137,27 @@ fn test_parse_invalid() {
     let invalid_inputs = vec!["idk 200", "fd-100", "fd", "FORWARD", "setcolor \"cat"];
 
     for input in invalid_inputs {
-        let result = parse_command(input);
+        let result = parse_command(input, &Arities::new());
         assert!(result.is_err(), "Oczekiwano błędu dla '{}', ale parsowanie zakończyło się sukcesem", input);
     }
 }
+
+#[test]
+fn test_parse_print_show_type() {
+    let test_cases = vec![
+        ("print :x", Command::Print(Unit::Var("x".to_string()))),
+        ("pr 1", Command::Print(Unit::Val(1.0))),
+        ("show [1 2]", Command::Show(Unit::List(vec![Unit::Val(1.0), Unit::Val(2.0)]))),
+        ("type \"a", Command::Type(Unit::Word("a".to_string()))),
+    ];
+
+    for (input, expected) in test_cases {
+        let result = parse_command(input, &Arities::new());
+        match result {
+            Ok((_, command)) => assert_eq!(command, expected),
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+    // showturtle is not show
+    assert_eq!(parse_command("showturtle", &Arities::new()), Ok(("", Command::ShowTurtle())));
+}
@@ -0,0 +1,97 @@
+use std::fs;
+
+use lista7::parser::block::Block;
+use lista7::parser::command::Command;
+use lista7::parser::program::{clean, parse};
+use lista7::parser::unit::Unit;
+use lista7::{Config, Interpreter, LogoError};
+
+fn parsed(input: &str) -> Vec<Block> {
+    match parse(input) {
+        Ok(blocks) => blocks,
+        Err(_) => panic!("Błąd parsowania dla '{}'", input),
+    }
+}
+
+#[test]
+fn test_comments() {
+    let expected = parsed("to square :size\n repeat 4 [ fd :size rt 90 ]\nend\nsquare 10");
+    let test_cases = vec![
+        "; square\nto square :size ; side length\n repeat 4 [ fd :size rt 90 ] ; rt 45\nend\n;square 20\nsquare 10 ; draw it",
+        "to square :size\n;\n repeat 4 [ fd :size rt 90 ];;\nend\n   ; to square :x\nsquare 10;",
+    ];
+
+    for input in test_cases {
+        assert_eq!(parsed(input), expected, "{}", input);
+    }
+    assert_eq!(parse("; nothing here\n  ; really"), Ok(vec![]));
+    // ; in a word is not a comment
+    assert_eq!(clean("print \"a\\;b ; c\nprint \"|x;y| ;z"), "print \"a\\;b\nprint \"|x;y|\n");
+}
+
+#[test]
+fn test_line_continuation() {
+    assert_eq!(
+        parsed("to tri :size :angle\n repeat 3 [ fd :size ~\n rt :angle ]\nend\ntri ~   \n  10 ~\n 120"),
+        parsed("to tri :size :angle\n repeat 3 [ fd :size rt :angle ]\nend\ntri 10 120")
+    );
+    // ~ inside of a line is not a continuation
+    assert_eq!(parsed("print \"a~b"), vec![Block::Single(Command::Print(Unit::Word("a~b".to_string())))]);
+    // comment after ~ is removed first
+    assert_eq!(parsed("fd 10 ~ ; more\n + 5"), parsed("fd 10 + 5"));
+}
+
+#[test]
+fn test_lines_in_errors_after_continuation() {
+    assert_eq!(clean("fd 1 ~\n2\nrt 3"), "fd 1  2\n\nrt 3\n");
+    assert_eq!(
+        parse("fd 10 ~\n + 5\nrt 90\n]"),
+        Err(LogoError::Parse("unexpected input at line 3: ]".to_string()))
+    );
+}
+
+#[test]
+fn test_crlf_and_tabs() {
+    let expected = parsed("to square :size\n repeat 4 [ fd :size rt 90 ]\nend\nsquare 10\nif 1 < 2 [ pu ]");
+    let test_cases = vec![
+        "to square :size\r\n repeat 4 [ fd :size rt 90 ]\r\nend\r\nsquare 10\r\nif 1 < 2 [ pu ]\r\n",
+        "to\tsquare\t:size\n\trepeat\t4\t[\tfd\t:size\trt 90\t]\nend\n\tsquare\t10\t\nif\t1\t<\t2\t[pu]",
+        "to square :size ; comment\r\n\trepeat 4 [ fd :size ~\r\n\t\trt 90 ]\r\nend\r\nsquare 10\r\nif 1 < 2 [ pu ]",
+    ];
+
+    for input in test_cases {
+        assert_eq!(parsed(input), expected, "{:?}", input);
+    }
+}
+
+#[test]
+fn test_case_insensitive_keywords() {
+    let expected = parsed("to star :n\n repeat 5 [ fd :n rt 144 ]\nend\npu setcolor \"red\nif random 2 < 2 [ star repcount ]");
+    let test_cases = vec![
+        "TO star :n\n REPEAT 5 [ FD :n RT 144 ]\nEND\nPU SETCOLOR \"red\nIF RANDOM 2 < 2 [ star REPCOUNT ]",
+        "To star :n\n Repeat 5 [ Forward :n Right 144 ]\nEnd\nPenUp SetColor \"red\nIf Random 2 < 2 [ star RepCount ]",
+    ];
+
+    for input in test_cases {
+        assert_eq!(parsed(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_same_drawing_from_reformatted_codes() {
+    for name in ["fern", "tree", "squares"] {
+        let code = fs::read_to_string(format!("codes/{}.txt", name)).unwrap();
+        // tabs, CRLF line ends and comments
+        let reformatted = code
+            .lines()
+            .map(|line| format!("\t{}\t; {}", line.trim().replace(' ', " \t"), name))
+            .collect::<Vec<_>>()
+            .join("\r\n");
+
+        let mut original = Interpreter::new(Config { seed: Some(1), ..Config::default() });
+        original.run_str(&code).unwrap();
+        let mut changed = Interpreter::new(Config { seed: Some(1), ..Config::default() });
+        changed.run_str(&reformatted).unwrap();
+        assert_eq!(original.turtle().lines, changed.turtle().lines, "{}", name);
+    }
+}
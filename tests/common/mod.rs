@@ -0,0 +1,26 @@
+// drawings shared by tests of the output formats; not every test file uses all of them
+#![allow(dead_code)]
+
+use lista7::evaluator::turtle::{Direction, Turn, Turtle};
+
+// black line up from the center, then red line to the right
+pub fn two_lines() -> Turtle {
+    let mut turtle = Turtle::new(200.0, 100.0);
+    turtle.go(Direction::Forward, 50.0);
+    turtle.change_color("red".to_string());
+    turtle.rotate(Turn::Right, 90.0);
+    turtle.go(Direction::Forward, 20.0);
+    turtle
+}
+
+// black line of turtle 0, then after a wait thicker red line of turtle 1
+pub fn two_turtles() -> Turtle {
+    let mut turtle = Turtle::new(200.0, 100.0);
+    turtle.go(Direction::Forward, 10.0);
+    turtle.wait(2.0);
+    turtle.set_turtle(1);
+    turtle.change_color("red".to_string());
+    turtle.pen_width = 4.5;
+    turtle.go(Direction::Back, 10.5);
+    turtle
+}
@@ -0,0 +1,110 @@
+use lista7::evaluator::turtle::Direction;
+use lista7::{interpreter, Config, Interpreter, LogoError};
+
+// result and number of drawn lines, from the evaluator and from the stack machine (they have to be the same)
+fn run_both(code: &str) -> (Result<(), LogoError>, usize) {
+    let mut tree = Interpreter::new(Config::default());
+    let tree_result = tree.run_str(code);
+    let mut vm = Interpreter::new(Config::default());
+    let vm_result = vm.run_compiled(&interpreter::parse(code).unwrap());
+
+    assert_eq!(tree_result, vm_result, "{}", code);
+    assert_eq!(tree.turtle().lines, vm.turtle().lines, "{}", code);
+    (tree_result, tree.turtle().lines.len())
+}
+
+#[test]
+fn test_call_before_definition() {
+    let test_cases = vec![
+        ("square 10\nto square :size\n repeat 4 [ fd :size rt 90 ]\nend", 4),
+        ("fd (double 5)\nto double :x\n output :x * 2\nend", 1),
+        // procedures calling each other, both defined below
+        ("tree 3\nto tree :n\n if :n > 0 [ fd 10 branch :n bk 10 ]\nend\nto branch :n\n lt 20 tree :n - 1 rt 40 tree :n - 1 lt 20\nend", 14),
+        // definitions inside of repeats, ifs and procedures are hoisted too, even if they would never run
+        ("f 5\nrepeat 0 [ to f :x\n fd :x\n end ]", 1),
+        ("g 5\nif 1 > 2 [ to g :x\n fd :x\n end\n fd 1 ]", 1),
+        ("inner 5\nto outer\n to inner :x\n  fd :x\n end\n inner 1\nend\nouter", 2),
+    ];
+
+    for (code, lines) in test_cases {
+        assert_eq!(run_both(code), (Ok(()), lines), "{}", code);
+    }
+}
+
+#[test]
+fn test_duplicate_definition() {
+    let test_cases = vec![
+        ("fd 10\nto f :x\n fd :x\nend\nto f :y\n bk :y\nend\nf 1", "f"),
+        // also inside another procedure
+        ("to f :x\n to g :y\n fd :y\n end\n g :x\nend\nto g :y\n bk :y\nend\nf 1", "g"),
+    ];
+
+    for (code, name) in test_cases {
+        assert_eq!(run_both(code), (Err(LogoError::DuplicateProcedure(name.to_string())), 0), "{}", code);
+    }
+}
+
+#[test]
+fn test_unknown_procedure_before_drawing() {
+    let test_cases = vec![
+        ("fd 10\nnothing 5", "nothing"),
+        // even when the call would never run
+        ("fd 10\nif 1 > 2 [ nothing ]", "nothing"),
+        ("to f :n\n fd :n\n if :n > 100 [ helper :n ]\nend\nf 10", "helper"),
+        ("fd 10 rt (angle 3)", "angle"),
+        ("repeat 4 [ fd 10 rt 90 ]\nfd 10 + (size)", "size"),
+    ];
+
+    for (code, name) in test_cases {
+        assert_eq!(run_both(code), (Err(LogoError::UnknownProcedure(name.to_string())), 0), "{}", code);
+    }
+}
+
+#[test]
+fn test_known_names() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.register_procedure("dot", 0, |turtle, _| {
+        turtle.go(Direction::Forward, 1.0);
+        Ok(())
+    });
+    // procedures from earlier runs, primitives and list procedures are known
+    interpreter.run_str("to side :n\n fd :n rt 90\nend").unwrap();
+    interpreter.run_str("side 10 dot\nforeach [1 2] [side ?]\nfd (first [5 6])").unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 5);
+
+    // procedure defined again in a later run replaces the old one
+    let angle = interpreter.turtle().angle;
+    interpreter.run_str("to side :n\n bk :n\nend\nside 1").unwrap();
+    assert_eq!(interpreter.turtle().angle, angle);
+
+    // names in lists are looked up when the list runs
+    assert_eq!(interpreter.run_str("fd 1 run [nothing]"), Err(LogoError::UnknownProcedure("nothing".to_string())));
+    assert_eq!(interpreter.turtle().lines.len(), 7);
+}
+
+#[test]
+fn test_names_are_case_insensitive() {
+    let test_cases = vec![
+        ("to Sq :size\n repeat 4 [ fd :size rt 90 ]\nend\nsq 10", 4),
+        ("SQ 10 Sq 20\nto sq :size\n repeat 4 [ fd :size rt 90 ]\nend", 8),
+        ("fd (Double 5)\nto DOUBLE :x\n output :x * 2\nend", 1),
+        ("to f :n\n fd :n\nend\nrepeat 2 [ F 10 ]", 2),
+    ];
+
+    for (code, lines) in test_cases {
+        assert_eq!(run_both(code), (Ok(()), lines), "{}", code);
+    }
+
+    // the same procedure written differently is defined twice
+    let code = "to f :x\n fd :x\nend\nto F :y\n bk :y\nend\nf 1";
+    assert_eq!(run_both(code), (Err(LogoError::DuplicateProcedure("f".to_string())), 0));
+
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.register_procedure("Dot", 0, |turtle, _| {
+        turtle.go(Direction::Forward, 1.0);
+        Ok(())
+    });
+    interpreter.run_str("to Side :n\n fd :n\nend").unwrap();
+    interpreter.run_str("dot DOT side 10 apply \"SIDE [5] run [SIDE 1] fd First [5 6]").unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 6);
+}
@@ -0,0 +1,31 @@
+use lista7::drawing::dxf::render_dxf;
+
+mod common;
+use common::two_lines;
+
+#[test]
+fn test_dxf_structure() {
+    let dxf = render_dxf(&two_lines(), 1.0);
+
+    assert!(dxf.starts_with("  0\nSECTION\n  2\nHEADER\n  9\n$ACADVER\n  1\nAC1009\n"));
+    assert!(dxf.ends_with("  0\nENDSEC\n  0\nEOF\n"));
+    assert_eq!(dxf.matches("  0\nLINE\n").count(), 2);
+}
+
+#[test]
+fn test_dxf_layers() {
+    let dxf = render_dxf(&two_lines(), 1.0);
+
+    assert!(dxf.contains("  0\nLAYER\n  2\nblack\n 70\n0\n 62\n7\n"));
+    assert!(dxf.contains("  0\nLAYER\n  2\nred\n 70\n0\n 62\n1\n"));
+}
+
+#[test]
+fn test_dxf_scaled_line() {
+    let dxf = render_dxf(&two_lines(), 0.5);
+
+    // second line: (100, 0) -> (120, 0) in turtle's coordinates, y axis flipped and scaled by half
+    assert!(dxf.contains(
+        "  0\nLINE\n  8\nred\n 10\n50.000\n 20\n50.000\n 30\n0.0\n 11\n60.000\n 21\n50.000\n 31\n0.0\n"
+    ));
+}
@@ -0,0 +1,102 @@
+use lista7::evaluator::value::Value;
+use lista7::evaluator::environment::Env;
+use lista7::{Config, Interpreter, LogoError};
+
+#[test]
+fn test_frames_shadow_and_restore() {
+    let mut env = Env::new();
+    env.set_var("size".to_string(), Value::Number(1.0));
+
+    env.push_frame("outer", &["size".to_string()], vec![Value::Number(2.0)]).unwrap();
+    env.push_frame("inner", &["size".to_string(), "n".to_string()], vec![Value::Number(3.0), Value::Number(4.0)]).unwrap();
+    assert_eq!(env.get_var("size"), Ok(Value::Number(3.0)));
+    assert_eq!(env.depth(), 2);
+
+    env.pop_frame();
+    assert_eq!(env.get_var("size"), Ok(Value::Number(2.0)));
+    assert_eq!(env.get_var("n"), Err(LogoError::UnknownVariable("n".to_string())));
+
+    env.pop_frame();
+    assert_eq!(env.get_var("size"), Ok(Value::Number(1.0)));
+    assert_eq!(env.depth(), 0);
+}
+
+#[test]
+fn test_set_var_changes_innermost_binding() {
+    let mut env = Env::new();
+    env.push_frame("f", &["x".to_string()], vec![Value::Number(1.0)]).unwrap();
+
+    env.set_var("x".to_string(), Value::Number(5.0));
+    env.set_var("y".to_string(), Value::Number(7.0));
+    env.pop_frame();
+
+    // x was local to the frame, y was created as a global
+    assert_eq!(env.get_var("x"), Err(LogoError::UnknownVariable("x".to_string())));
+    assert_eq!(env.get_var("y"), Ok(Value::Number(7.0)));
+}
+
+#[test]
+fn test_push_frame_wrong_arity() {
+    let mut env = Env::new();
+
+    let result = env.push_frame("f", &["x".to_string()], vec![]);
+
+    assert_eq!(result, Err(LogoError::WrongArity { name: "f".to_string(), expected: 1, got: 0 }));
+    assert_eq!(env.depth(), 0);
+}
+
+#[test]
+fn test_repcount() {
+    let mut env = Env::new();
+    assert_eq!(env.repcount(), -1.0);
+
+    env.push_loop();
+    env.next_iteration();
+    env.next_iteration();
+    env.push_loop();
+    env.next_iteration();
+    assert_eq!(env.repcount(), 1.0);
+
+    env.pop_loop();
+    assert_eq!(env.repcount(), 2.0);
+}
+
+#[test]
+fn test_repcount_in_program() {
+    let mut interpreter = Interpreter::new(Config::default());
+
+    interpreter.run_str("repeat 3 [ repeat 2 [ fd repcount ] rt 90 fd # ]").unwrap();
+
+    let lengths: Vec<f64> = interpreter.turtle().lines.iter()
+        .map(|line| ((line.end_x - line.start_x).powi(2) + (line.end_y - line.start_y).powi(2)).sqrt().round())
+        .collect();
+    assert_eq!(lengths, vec![1.0, 2.0, 1.0, 1.0, 2.0, 2.0, 1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn test_scopes_cleaned_after_stop_and_error() {
+    let mut interpreter = Interpreter::new(Config::default());
+    let program = "to f :x\n repeat 5 [ if :x > 0 [ stop ] ]\nend\nf 1\n";
+
+    interpreter.run_str(program).unwrap();
+    assert_eq!(interpreter.run_str("fd :x"), Err(LogoError::UnknownVariable("x".to_string())));
+    assert_eq!(interpreter.run_str("to g :x\n fd :y\nend\ng 1"), Err(LogoError::UnknownVariable("y".to_string())));
+    assert_eq!(interpreter.run_str("fd :x"), Err(LogoError::UnknownVariable("x".to_string())));
+}
+
+#[test]
+fn test_tail_frame_reuses_scope() {
+    let mut env = Env::new();
+    env.push_frame("outer", &["size".to_string(), "n".to_string()], vec![Value::Number(10.0), Value::Number(1.0)]).unwrap();
+
+    env.tail_frame("inner", &["n".to_string(), "k".to_string()], vec![Value::Number(2.0), Value::Number(3.0)]).unwrap();
+    // caller's variables are still visible, parameters bound twice have one value
+    assert_eq!(env.get_var("size"), Ok(Value::Number(10.0)));
+    assert_eq!(env.get_var("n"), Ok(Value::Number(2.0)));
+    assert_eq!(env.depth(), 1);
+
+    env.pop_frame();
+    for name in ["size", "n", "k"] {
+        assert_eq!(env.get_var(name), Err(LogoError::UnknownVariable(name.to_string())));
+    }
+}
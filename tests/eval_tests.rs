@@ -25,7 +25,7 @@ fn test_program_evaluation() {
     let mut env = Env::new();
     let mut turtle = Turtle::new(800.0, 600.0);
 
-    eval(parsed_program, &mut turtle, &mut env);
+    eval(parsed_program, &mut turtle, &mut env).unwrap();
 
     assert_eq!(turtle.x, 400.0);
     assert_eq!(turtle.y, 300.0);
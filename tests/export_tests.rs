@@ -0,0 +1,53 @@
+use lista7::drawing::{pdf::render_pdf, eps::render_eps};
+
+mod common;
+use common::two_lines;
+
+#[test]
+fn test_pdf_structure() {
+    let pdf = render_pdf(&two_lines());
+
+    assert!(pdf.starts_with("%PDF-1.4\n"));
+    assert!(pdf.ends_with("%%EOF\n"));
+    assert!(pdf.contains("/MediaBox [0 0 200 100]"));
+}
+
+#[test]
+fn test_pdf_lines_and_colors() {
+    let pdf = render_pdf(&two_lines());
+
+    // y axis is flipped: turtle starts at (100, 50) and goes up by 50
+    assert!(pdf.contains("0.000 0.000 0.000 RG\n100.00 50.00 m 100.00 100.00 l S"));
+    assert!(pdf.contains("1.000 0.000 0.000 RG\n100.00 100.00 m 120.00 100.00 l S"));
+}
+
+#[test]
+fn test_pdf_xref_offsets() {
+    let pdf = render_pdf(&two_lines());
+
+    let startxref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+    assert!(pdf[startxref..].starts_with("xref"));
+
+    let entries: Vec<&str> = pdf[startxref..].lines().skip(3).take(4).collect();
+    for (i, entry) in entries.iter().enumerate() {
+        let offset: usize = entry[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+    }
+}
+
+#[test]
+fn test_eps_structure() {
+    let eps = render_eps(&two_lines());
+
+    assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n"));
+    assert!(eps.contains("%%BoundingBox: 0 0 200 100\n"));
+    assert!(eps.ends_with("showpage\n%%EOF\n"));
+}
+
+#[test]
+fn test_eps_lines_and_colors() {
+    let eps = render_eps(&two_lines());
+
+    assert!(eps.contains("0.000 0.000 0.000 setrgbcolor\nnewpath 100.00 50.00 moveto 100.00 100.00 lineto stroke"));
+    assert!(eps.contains("1.000 0.000 0.000 setrgbcolor\nnewpath 100.00 100.00 moveto 120.00 100.00 lineto stroke"));
+}
@@ -0,0 +1,144 @@
+use std::fs;
+
+use lista7::parser::block::{Block, CmpOp, Condition};
+use lista7::parser::command::Command;
+use lista7::parser::format::format;
+use lista7::parser::program::parse;
+use lista7::parser::unit::{ArithOp, Unit};
+use lista7::{Config, Interpreter};
+
+fn parsed(input: &str) -> Vec<Block> {
+    match parse(input) {
+        Ok(blocks) => blocks,
+        Err(_) => panic!("Błąd parsowania dla '{}'", input),
+    }
+}
+
+fn val(n: f64) -> Box<Unit> {
+    Box::new(Unit::Val(n))
+}
+
+fn word(w: &str) -> Unit {
+    Unit::Word(w.to_string())
+}
+
+// formatted code gives the same AST back, and formatting it again doesn't change it
+fn assert_round_trip(blocks: &[Block]) {
+    let code = format(blocks);
+    assert_eq!(parsed(&code), blocks, "{}", code);
+    assert_eq!(format(&parsed(&code)), code);
+}
+
+#[test]
+fn test_format() {
+    let program = "TO square :size\nRepeat 4 [Forward :size Right 90]\nEND\nclearscreen penup  fd 10\nif :x<5 [square 10 setcolor \"red]";
+    let expected = "to square :size\n  repeat 4 [ forward :size right 90 ]\nend\n\nclearscreen\npenup\nforward 10\nif :x < 5 [ square 10 setcolor \"red ]\n";
+    assert_eq!(format(&parsed(program)), expected);
+    assert_eq!(format(&[]), "");
+    // negative number is a separate input, so it doesn't need parentheses
+    assert_eq!(format(&parsed("to f :a :b\noutput :a\nend\nf 1 -2 print f :x -1")), "to f :a :b\n  output :a\nend\n\nf 1 -2\nprint (f :x -1)\n");
+}
+
+#[test]
+fn test_long_body_in_lines() {
+    let program = "repeat 10 [ fd 100 rt 90 fd 100 rt 90 fd 100 rt 90 fd 100 rt 90 fd 100 rt 90 fd 100 rt 90 ]\nrepeat 2 [ repeat 3 [ fd 1 ] ]";
+    let expected = "repeat 10 [\n  forward 100\n  right 90\n  forward 100\n  right 90\n  forward 100\n  right 90\n  forward 100\n  right 90\n  forward 100\n  right 90\n  forward 100\n  right 90\n]\nrepeat 2 [\n  repeat 3 [ forward 1 ]\n]\n";
+    assert_eq!(format(&parsed(program)), expected);
+}
+
+#[test]
+fn test_round_trip_programs() {
+    let programs = vec![
+        "to tree :size\n if :size < 5 [ stop ]\n fd :size / 3 tree :size * 0.66 bk :size\nend\ntree 150",
+        "to f :a :b\n output :a + :b\nend\nprint f 1 -2\nf 1 2 f 3 4\n(f 1 2 3)\n(f 1)\nfd (f 1 (random 2 -3)) * 2",
+        "unknown 1 -2 \"a [b c]\nunknown\nrepeat 3 [ unknown 5 ]",
+        "if (emptyp [1 2]) [ pu ]\nif \"true == \"true [ pd ]\nsetcolor pick [red |light blue| violet]",
+        "print [1 |2| \\3 ( ) [] [a\\ b] |a;b| \\~ -]\nprint \"\nshow \"a\\ b\\[c\\]\nprint (list [|| x] \"|(|)",
+        "print (random 1 6) + (randomfloat 2)\nfd random 10\nrerandom\nrerandom 5\nprint repcount - 1",
+        "to outer\n to inner :x\n  fd :x\n end\n inner 10\nend\nouter",
+        "hideturtle showturtle window wait 1 setturtle 2 type 1.5 lt -90 bk 0.25",
+        "fd 1e3 rt 2.5E-1 print random 1e300",
+    ];
+
+    for program in programs {
+        assert_round_trip(&parsed(program));
+    }
+}
+
+#[test]
+fn test_round_trip_list_primitives() {
+    let program = "to f :l\n print first :l print count [a b c]\n foreach :l [ print ? ]\nend\nf [x y]\nprint item 2 map [? * 2] [1 2 3]";
+    let blocks = parsed(program);
+    let first = Unit::Call("first".to_string(), vec![Unit::Var("l".to_string())]);
+    let count = Unit::Call("count".to_string(), vec![Unit::List(vec![word("a"), word("b"), word("c")])]);
+    match &blocks[0] {
+        Block::Function(_, _, body) => {
+            assert_eq!(body[0], Block::Single(Command::Print(first)));
+            assert_eq!(body[1], Block::Single(Command::Print(count)));
+        }
+        block => panic!("{:?}", block),
+    }
+    assert_round_trip(&blocks);
+
+    // formatted program runs the same
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str(&format(&blocks)).unwrap();
+    assert_eq!(interpreter.output(), "x\n3\nx\ny\n4\n");
+
+    // registered reporters are known to the interpreter's formatter, like to its parser
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.register_reporter("half", 1, |args| Ok(args[0] / 2.0));
+    let blocks = interpreter.parse("fd half 10 rt 90").unwrap();
+    assert_eq!(blocks.len(), 2);
+    let code = interpreter.format(&blocks);
+    assert_eq!(interpreter.parse(&code).unwrap(), blocks);
+    interpreter.run_str(&code).unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 1);
+}
+
+#[test]
+fn test_round_trip_nested_expressions() {
+    let sum = Unit::Exp(val(1.0), ArithOp::Add, Box::new(Unit::Var("a".to_string())));
+    let units = vec![
+        Unit::Exp(Box::new(sum.clone()), ArithOp::Mul, val(2.0)),
+        Unit::Exp(val(2.0), ArithOp::Div, Box::new(sum.clone())),
+        Unit::Exp(Box::new(sum.clone()), ArithOp::Sub, Box::new(Unit::Exp(val(-1.0), ArithOp::Sub, val(-2.0)))),
+        Unit::Exp(Box::new(Unit::Random(val(5.0))), ArithOp::Add, Box::new(Unit::RandomFloat(val(1.0)))),
+        Unit::Exp(Box::new(word("a")), ArithOp::Add, Box::new(Unit::List(vec![Unit::Val(1.0)]))),
+        Unit::Exp(Box::new(Unit::RandomRange(val(-1.0), Box::new(sum.clone()))), ArithOp::Add, Box::new(Unit::RepCount)),
+    ];
+
+    for unit in units {
+        assert_round_trip(&[
+            Block::Single(Command::Forward(unit.clone())),
+            Block::Call("unknown".to_string(), vec![Unit::Val(1.0), unit.clone(), Unit::Val(-3.0)]),
+            Block::If(Condition { left: unit.clone(), operator: CmpOp::Greater, right: *val(-1.0) }, vec![Block::Single(Command::PenUp())]),
+        ]);
+    }
+}
+
+#[test]
+fn test_round_trip_words() {
+    let words = vec!["", "a b", "[x]", "(", ")", "a|b", "\\", ";", "~", "10", "-1.5", "1e5", "\"", ":x", "ąę"];
+
+    for w in words {
+        assert_round_trip(&[
+            Block::Single(Command::Print(word(w))),
+            Block::Single(Command::Print(Unit::List(vec![word(w), Unit::List(vec![word(w)])]))),
+        ]);
+    }
+}
+
+#[test]
+fn test_round_trip_codes() {
+    for entry in fs::read_dir("codes").unwrap() {
+        let path = entry.unwrap().path();
+        assert_round_trip(&parsed(&fs::read_to_string(&path).unwrap()));
+    }
+}
+
+#[test]
+fn test_fixture_is_formatted() {
+    let code = fs::read_to_string("tests/fixtures/formatted.txt").unwrap();
+    assert_eq!(format(&parsed(&code)), code);
+}
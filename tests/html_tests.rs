@@ -0,0 +1,29 @@
+use lista7::evaluator::turtle::{Direction, Turtle};
+use lista7::drawing::html::render_html;
+
+mod common;
+use common::two_turtles;
+
+#[test]
+fn test_html_embeds_drawing() {
+    let html = render_html(&two_turtles(), "race");
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>race</title>"));
+    assert!(html.contains("const drawing = {\"version\":1,\"width\":200,\"height\":100,\"lines\":["));
+    assert!(html.contains("{\"x1\":100,\"y1\":50,\"x2\":100,\"y2\":40,\"color\":\"black\",\"width\":2,\"turtle\":0,\"time\":0}"));
+    assert!(html.contains("{\"x1\":100,\"y1\":50,\"x2\":100,\"y2\":60.5,\"color\":\"red\",\"width\":4.5,\"turtle\":1,\"time\":2}"));
+}
+
+#[test]
+fn test_html_escapes_text() {
+    let mut turtle = Turtle::new(10.0, 10.0);
+    turtle.change_color("</script>".to_string());
+    turtle.go(Direction::Forward, 1.0);
+
+    let html = render_html(&turtle, "<b>");
+
+    assert!(html.contains("<title>&lt;b&gt;</title>"));
+    assert!(html.contains("\"\\u003c/script>\""));
+    assert_eq!(html.matches("</script>").count(), 1);
+}
@@ -0,0 +1,59 @@
+use lista7::evaluator::value::Value;
+use lista7::parser::{block::Block, command::Command};
+use lista7::{Config, Interpreter, LogoError};
+
+#[test]
+fn test_run_str() {
+    let mut interpreter = Interpreter::new(Config::default());
+
+    interpreter.run_str("repeat 4 [ fd 100 rt 90 ]").unwrap();
+
+    assert_eq!(interpreter.turtle().lines.len(), 4);
+    assert_eq!(interpreter.turtle().img_x, 800.0);
+}
+
+#[test]
+fn test_run_file() {
+    let mut interpreter = Interpreter::new(Config { width: 200.0, height: 100.0, ..Config::default() });
+
+    interpreter.run_file("codes/star.txt").unwrap();
+
+    let turtle = interpreter.into_turtle();
+    assert_eq!(turtle.img_x, 200.0);
+    assert_eq!(turtle.lines.len(), 5);
+}
+
+#[test]
+fn test_procedures_kept_between_runs() {
+    let mut interpreter = Interpreter::new(Config::default());
+
+    interpreter.run_str("to line :n\n fd :n\nend").unwrap();
+    interpreter.run_str("line 10 line 20").unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 2);
+
+    interpreter.reset();
+    assert_eq!(interpreter.turtle().lines.len(), 0);
+    assert_eq!(interpreter.run_str("line 10"), Err(LogoError::UnknownProcedure("line".to_string())));
+}
+
+#[test]
+fn test_errors() {
+    let mut interpreter = Interpreter::new(Config::default());
+
+    assert!(matches!(interpreter.run_file("codes/missing.txt"), Err(LogoError::Io(_))));
+    assert_eq!(
+        interpreter.run_str("fd 10\nrt 90 ]"),
+        Err(LogoError::Parse("unexpected input at line 2: ]".to_string()))
+    );
+    assert_eq!(interpreter.run_str("fd :x"), Err(LogoError::UnknownVariable("x".to_string())));
+    assert_eq!(interpreter.run_str(""), Ok(()));
+}
+
+#[test]
+fn test_pick_from_empty_list() {
+    let blocks = vec![Block::Single(Command::SetColorPick(vec![]))];
+    let expected = Err(LogoError::BadInput { name: "pick".to_string(), value: Value::List(vec![]) });
+
+    assert_eq!(Interpreter::new(Config::default()).run_blocks(blocks.clone()), expected);
+    assert_eq!(Interpreter::new(Config::default()).run_compiled(&blocks), expected);
+}
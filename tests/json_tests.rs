@@ -0,0 +1,78 @@
+use lista7::drawing::json::{parse_json, render_json};
+
+mod common;
+use common::two_turtles;
+
+#[test]
+fn test_render_json() {
+    let json = render_json(&two_turtles());
+
+    assert_eq!(
+        json,
+        "{\"version\":1,\"width\":200,\"height\":100,\"lines\":[\n\
+         {\"x1\":100,\"y1\":50,\"x2\":100,\"y2\":40,\"color\":\"black\",\"width\":2,\"turtle\":0,\"time\":0},\n\
+         {\"x1\":100,\"y1\":50,\"x2\":100,\"y2\":60.5,\"color\":\"red\",\"width\":4.5,\"turtle\":1,\"time\":2}\n\
+         ]}\n"
+    );
+}
+
+#[test]
+fn test_json_round_trip() {
+    let turtle = two_turtles();
+    let loaded = parse_json(&render_json(&turtle)).unwrap();
+
+    assert_eq!((loaded.img_x, loaded.img_y), (200.0, 100.0));
+    assert_eq!(loaded.lines.len(), 2);
+    assert_eq!(loaded.lines[1].color, "red");
+    assert_eq!(loaded.lines[1].width, 4.5);
+    assert_eq!(loaded.lines[1].turtle, 1);
+    assert_eq!(loaded.lines[1].time, 2.0);
+    assert_eq!(render_json(&loaded), render_json(&turtle));
+}
+
+#[test]
+fn test_parse_json_defaults() {
+    let turtle = parse_json(r#" { "width": 10, "height": 20.5, "lines": [
+        { "x1": 1, "y1": 2, "x2": 3e0, "y2": -4, "color": "blue" }
+    ] } "#).unwrap();
+
+    assert_eq!(turtle.img_y, 20.5);
+    let line = &turtle.lines[0];
+    assert_eq!((line.start_x, line.start_y, line.end_x, line.end_y), (1.0, 2.0, 3.0, -4.0));
+    assert_eq!(line.color, "blue");
+    assert_eq!(line.width, 2.0);
+    assert_eq!(line.turtle, 0);
+    assert_eq!(line.time, 0.0);
+}
+
+#[test]
+fn test_parse_json_errors() {
+    assert!(parse_json("").is_err());
+    assert!(parse_json("[]").is_err());
+    assert!(parse_json(r#"{"width": 1, "height": 1}"#).is_err());
+    assert!(parse_json(r#"{"width": 1, "height": 1, "lines": [{"x1": 0}]}"#).is_err());
+    assert!(parse_json(r#"{"version": 2, "width": 1, "height": 1, "lines": []}"#).is_err());
+    assert!(parse_json(r#"{"width": 1, "height": 1, "lines": []} x"#).is_err());
+}
+
+#[test]
+fn test_parse_json_unicode_escapes() {
+    let turtle = parse_json(r#"{"width": 1, "height": 1, "extra": "\ud83d\ude00", "lines": [
+        {"x1": 0, "y1": 0, "x2": 1, "y2": 1, "color": "r\u00e9d \uD83D\uDE00"}
+    ]}"#).unwrap();
+    assert_eq!(turtle.lines[0].color, "réd 😀");
+
+    for surrogate in [r#""\ud83d""#, r#""\ud83dx""#, r#""\ud83dA""#, r#""\ude00""#] {
+        let json = format!(r#"{{"width": 1, "height": 1, "lines": [], "extra": {}}}"#, surrogate);
+        assert!(parse_json(&json).is_err(), "{}", surrogate);
+    }
+}
+
+#[test]
+fn test_parse_json_too_deep() {
+    let nested = |depth: usize| {
+        format!(r#"{{"width": 1, "height": 1, "lines": [], "extra": {}{}}}"#, "[".repeat(depth), "]".repeat(depth))
+    };
+    assert!(parse_json(&nested(100)).is_ok());
+    assert_eq!(parse_json(&nested(1_000_000)).err().unwrap(), "json: nested deeper than 128 levels at byte 175");
+}
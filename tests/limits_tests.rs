@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use lista7::evaluator::limits::{Limits, DEFAULT_MAX_DEPTH};
+use lista7::{Config, Interpreter, LogoError};
+
+fn interpreter(limits: Limits) -> Interpreter {
+    Interpreter::new(Config { limits, ..Config::default() })
+}
+
+#[test]
+fn test_step_limit() {
+    let mut interpreter = interpreter(Limits { max_steps: Some(1000), ..Limits::unlimited() });
+
+    assert_eq!(interpreter.run_str("repeat 1000000000000 [ pu ]"), Err(LogoError::StepLimit(1000)));
+
+    // every run has its own budget
+    assert_eq!(interpreter.run_str("repeat 10 [ fd 1 ]"), Ok(()));
+}
+
+#[test]
+fn test_depth_limit() {
+    let mut interpreter = interpreter(Limits { max_depth: Some(50), ..Limits::unlimited() });
+    let program = "to down :n\n if :n < 1 [ stop ]\n down :n - 1\n rt 1\nend\n";
+
+    assert_eq!(interpreter.run_str(&format!("{}down 49", program)), Ok(()));
+    assert_eq!(interpreter.run_str("down 50"), Err(LogoError::DepthLimit(50)));
+    // depth is restored after error, so the next run can go as deep again
+    assert_eq!(interpreter.run_str("down 49"), Ok(()));
+}
+
+#[test]
+fn test_default_depth_limit_without_base_case() {
+    let mut interpreter = Interpreter::new(Config::default());
+
+    let result = interpreter.run_str("to fern :size\n fd :size\n fern :size * 0.5\n bk :size\nend\nfern 25");
+
+    assert_eq!(result, Err(LogoError::DepthLimit(DEFAULT_MAX_DEPTH)));
+}
+
+#[test]
+fn test_tail_recursion_without_base_case_is_a_loop() {
+    // tail calls are not nested, so only steps can stop it
+    let mut interpreter = interpreter(Limits { max_steps: Some(10000), ..Limits::default() });
+
+    let result = interpreter.run_str("to fern :size\n fd :size\n fern :size * 0.5\nend\nfern 25");
+
+    assert_eq!(result, Err(LogoError::StepLimit(10000)));
+}
+
+#[test]
+fn test_line_limit() {
+    let mut interpreter = interpreter(Limits { max_lines: Some(10), ..Limits::unlimited() });
+
+    assert_eq!(interpreter.run_str("repeat 100 [ fd 1 ]"), Err(LogoError::LineLimit(10)));
+    assert_eq!(interpreter.turtle().lines.len(), 11);
+}
+
+#[test]
+fn test_time_limit() {
+    let max = Duration::from_millis(50);
+    let mut interpreter = interpreter(Limits { max_time: Some(max), ..Limits::unlimited() });
+
+    assert_eq!(interpreter.run_str("repeat 1000000000000 [ pu ]"), Err(LogoError::TimeLimit(max)));
+}
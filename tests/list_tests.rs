@@ -0,0 +1,145 @@
+use lista7::evaluator::{environment::Env, eval::eval_unit, turtle::Turtle, value::Value};
+use lista7::parser::unit::parse_unit;
+use lista7::{interpreter, Config, Interpreter, LogoError};
+use lista7::parser::program::Arities;
+
+fn eval_str(input: &str) -> Result<Value, LogoError> {
+    let (_, unit) = parse_unit(input, &Arities::new()).unwrap();
+    eval_unit(&unit, &mut Turtle::new(800.0, 600.0), &mut Env::with_seed(1))
+}
+
+fn list(items: &[f64]) -> Value {
+    Value::List(items.iter().map(|n| Value::Number(*n)).collect())
+}
+
+fn word(w: &str) -> Value {
+    Value::Word(w.to_string())
+}
+
+#[test]
+fn test_list_primitives() {
+    let test_cases = vec![
+        ("(first [1 2 3])", Value::Number(1.0)),
+        ("(butfirst [1 2 3])", list(&[2.0, 3.0])),
+        ("(bf [1])", list(&[])),
+        ("(last [1 2 3])", Value::Number(3.0)),
+        ("(butlast [1 2 3])", list(&[1.0, 2.0])),
+        ("(item 2 [1 2 3])", Value::Number(2.0)),
+        ("(count [1 [2 3] 4])", Value::Number(3.0)),
+        ("(fput 0 [1 2])", list(&[0.0, 1.0, 2.0])),
+        ("(lput 3 [1 2])", list(&[1.0, 2.0, 3.0])),
+        ("(list 1 [2])", Value::List(vec![Value::Number(1.0), list(&[2.0])])),
+        ("(sentence 1 [2 3] [])", list(&[1.0, 2.0, 3.0])),
+        ("(emptyp [])", Value::Bool(true)),
+        ("(emptyp [1])", Value::Bool(false)),
+        ("(memberp 2 [1 2 3])", Value::Bool(true)),
+        ("(memberp [2] [1 2 3])", Value::Bool(false)),
+        ("(first (bf [[0 0] [10 20]]))", list(&[10.0, 20.0])),
+    ];
+
+    for (input, expected) in test_cases {
+        assert_eq!(eval_str(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_word_primitives() {
+    let test_cases = vec![
+        ("(first \"red)", word("r")),
+        ("(butfirst \"red)", word("ed")),
+        ("(last \"red)", word("d")),
+        ("(item 2 \"red)", word("e")),
+        ("(count \"red)", Value::Number(3.0)),
+        ("(fput \"b \"ed)", word("bed")),
+        ("(memberp \"E \"red)", Value::Bool(true)),
+        ("(memberp \"el \"hello)", Value::Bool(false)),
+        ("(memberp \"| | \"hello)", Value::Bool(false)),
+        ("(memberp 2 \"123)", Value::Bool(true)),
+        ("(memberp [e] \"red)", Value::Bool(false)),
+        ("(emptyp \"red)", Value::Bool(false)),
+    ];
+
+    for (input, expected) in test_cases {
+        assert_eq!(eval_str(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_list_primitive_errors() {
+    let bad_input = |name: &str, value: Value| Err(LogoError::BadInput { name: name.to_string(), value });
+
+    assert_eq!(eval_str("(first [])"), bad_input("first", list(&[])));
+    assert_eq!(eval_str("(item 4 [1 2 3])"), bad_input("item", Value::Number(4.0)));
+    assert_eq!(eval_str("(fput \"ab \"cd)"), bad_input("fput", word("ab")));
+    assert_eq!(eval_str("(first [a b]) + 1"), bad_input("sum", word("a")));
+    assert_eq!(
+        eval_str("(first 1 2)"),
+        Err(LogoError::WrongArity { name: "first".to_string(), expected: 1, got: 2 })
+    );
+}
+
+#[test]
+fn test_values_equality_and_printing() {
+    // words that look like numbers are numbers, other words are compared ignoring case
+    assert!(word("10").equals(&Value::Number(10.0)));
+    assert!(word("Red").equals(&word("red")));
+    assert!(!list(&[1.0]).equals(&Value::Number(1.0)));
+    assert_eq!(word("10").as_number("fd"), Ok(10.0));
+
+    let nested = Value::List(vec![word("red"), list(&[1.0, 2.5])]);
+    assert_eq!(nested.to_string(), "red [1 2.5]");
+    assert_eq!(nested.show(), "[red [1 2.5]]");
+    assert_eq!(
+        LogoError::BadInput { name: "fd".to_string(), value: nested }.to_string(),
+        "fd doesn't like [red [1 2.5]] as input"
+    );
+}
+
+#[test]
+fn test_program_with_points_and_palette() {
+    let program = "to path :points :colors\n\
+                   if (emptyp :points) [ stop ]\n\
+                   setcolor (first :colors)\n\
+                   fd (first (first :points)) rt (last (first :points))\n\
+                   path (bf :points) (lput (first :colors) (bf :colors))\n\
+                   end\n\
+                   path [[10 90] [20 90] [30 90]] [red blue]";
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str(program).unwrap();
+
+    let lines = &interpreter.turtle().lines;
+    assert_eq!(lines.len(), 3);
+    let colors: Vec<&str> = lines.iter().map(|line| line.color.as_str()).collect();
+    assert_eq!(colors, ["red", "blue", "red"]);
+    assert_eq!(interpreter.turtle().angle, 270.0 + 270.0);
+}
+
+#[test]
+fn test_list_primitive_is_not_a_command() {
+    let mut interpreter = Interpreter::new(Config::default());
+    assert_eq!(interpreter.run_str("first [1 2]"), Err(LogoError::NotACommand("first".to_string())));
+}
+
+#[test]
+fn test_compiler_rejects_words_and_lists() {
+    let interpreter = Interpreter::new(Config::default());
+    let blocks = interpreter::parse("fd (count [1 2])").unwrap();
+    assert_eq!(interpreter.compile(&blocks).err(), Some(LogoError::Unsupported("count".to_string())));
+}
+
+#[test]
+fn test_parse_knows_list_primitives() {
+    let program = "print first [a b] print count [a b c] fd item 2 [10 20] rt 90";
+    let interpreter = Interpreter::new(Config::default());
+    let blocks = interpreter::parse(program).unwrap();
+    assert_eq!(blocks.len(), 4);
+    assert_eq!(interpreter.parse(program).unwrap(), blocks);
+}
+
+#[test]
+fn test_if_with_bare_predicate_runs() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str("if emptyp [] [ fd 10 ]\nif emptyp [1] [ fd 20 ]\nif memberp 2 [1 2] [ rt 90 ]").unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 1);
+    assert_eq!(interpreter.turtle().angle, 270.0 + 90.0);
+}
@@ -0,0 +1,112 @@
+use std::fs;
+
+use lista7::evaluator::optimize::optimize;
+use lista7::parser::block::Block;
+use lista7::parser::command::Command;
+use lista7::parser::unit::{ArithOp, Unit};
+use lista7::{interpreter, Config, Interpreter};
+
+fn optimized(code: &str) -> Vec<Block> {
+    optimize(interpreter::parse(code).unwrap())
+}
+
+fn var(name: &str) -> Unit {
+    Unit::Var(name.to_string())
+}
+
+#[test]
+fn test_fold_constants() {
+    assert_eq!(optimized("rt 4 * 90"), vec![Block::Single(Command::Right(Unit::Val(360.0)))]);
+    // only the constant part can be folded
+    assert_eq!(
+        optimized("rt 70 * :sign"),
+        vec![Block::Single(Command::Right(Unit::Exp(Box::new(Unit::Val(70.0)), ArithOp::Mul, Box::new(var("sign")))))]
+    );
+}
+
+#[test]
+fn test_unroll_repeat() {
+    assert_eq!(
+        optimized("repeat 2 [ fd repcount * 10 ]"),
+        vec![Block::Single(Command::Forward(Unit::Val(10.0))), Block::Single(Command::Forward(Unit::Val(20.0)))]
+    );
+    assert_eq!(optimized("repeat 0 [ fd 10 ]"), vec![]);
+
+    // too big, or with repcount that could be read by the called procedure
+    assert!(matches!(optimized("repeat 100 [ fd 10 ]")[..], [Block::Repeat(..)]));
+    assert!(matches!(optimized("to f :n\n if :n > 1 [ stop ]\n fd repcount\nend\nrepeat 2 [ f 1 ]")[..], [Block::Function(..), Block::Repeat(..)]));
+}
+
+#[test]
+fn test_nested_repeat_keeps_its_repcount() {
+    assert_eq!(
+        optimized("repeat 2 [ repeat 100 [ fd repcount ] rt repcount ]"),
+        vec![
+            Block::Repeat(Unit::Val(100.0), vec![Block::Single(Command::Forward(Unit::RepCount))]),
+            Block::Single(Command::Right(Unit::Val(1.0))),
+            Block::Repeat(Unit::Val(100.0), vec![Block::Single(Command::Forward(Unit::RepCount))]),
+            Block::Single(Command::Right(Unit::Val(2.0))),
+        ]
+    );
+}
+
+#[test]
+fn test_remove_dead_if() {
+    assert_eq!(optimized("if 1 < 2 [ fd 10 ] if 2 * 2 == 5 [ bk 10 ]"), vec![Block::Single(Command::Forward(Unit::Val(10.0)))]);
+    assert!(matches!(optimized("if :x < 2 [ fd 10 ]")[..], [Block::If(..)]));
+}
+
+#[test]
+fn test_inline_trivial_procedure() {
+    let blocks = optimized("to sq :n\n repeat 4 [ fd :n rt 90 ]\nend\nsq 2 * 5");
+
+    assert_eq!(blocks.len(), 9);
+    assert_eq!(blocks[1], Block::Single(Command::Forward(Unit::Val(10.0))));
+    assert_eq!(blocks[8], Block::Single(Command::Right(Unit::Val(90.0))));
+}
+
+#[test]
+fn test_inline_above_definition() {
+    // definitions are hoisted, so the call runs after it anyway
+    let blocks = optimized("sq 5\nto sq :n\n fd :n\nend");
+    assert_eq!(blocks[0], Block::Single(Command::Forward(Unit::Val(5.0))));
+}
+
+#[test]
+fn test_no_inline() {
+    // called with random argument, recursive, or with stop
+    let programs = [
+        "to sq :n\n fd :n rt :n\nend\nsq random 10",
+        "to down :n\n fd :n\n down :n - 1\nend\ndown 5",
+        "to f :n\n if :n > 1 [ stop ]\n fd :n\nend\nf 1",
+    ];
+    for program in programs {
+        let blocks = optimized(program);
+        assert!(blocks.iter().any(|block| matches!(block, Block::Call(..))), "{}", program);
+    }
+}
+
+#[test]
+fn test_same_drawing_on_codes() {
+    for entry in fs::read_dir("codes").unwrap() {
+        let path = entry.unwrap().path();
+        let code = fs::read_to_string(&path).unwrap();
+
+        let mut plain = Interpreter::new(Config { seed: Some(3), ..Config::default() });
+        plain.run_str(&code).unwrap();
+        let mut optimized = Interpreter::new(Config { seed: Some(3), optimize: true, ..Config::default() });
+        optimized.run_str(&code).unwrap();
+        let mut compiled = Interpreter::new(Config { seed: Some(3), optimize: true, ..Config::default() });
+        compiled.run_compiled(&interpreter::parse(&code).unwrap()).unwrap();
+
+        assert_eq!(optimized.turtle().lines, plain.turtle().lines, "{}", path.display());
+        assert_eq!(compiled.turtle().lines, plain.turtle().lines, "{}", path.display());
+    }
+}
+
+#[test]
+fn test_repeat_with_run_is_not_unrolled() {
+    // repcount in the list is only a word, until the list is run in the loop
+    let blocks = interpreter::parse("repeat 2 [ fd (run [repcount * 10]) ]").unwrap();
+    assert_eq!(optimize(blocks.clone()), blocks);
+}
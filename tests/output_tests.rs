@@ -0,0 +1,76 @@
+use lista7::evaluator::output::Output;
+use lista7::{interpreter, Config, Interpreter, LogoError};
+
+fn printed(code: &str) -> String {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str(code).unwrap();
+    interpreter.output().to_string()
+}
+
+#[test]
+fn test_print_show_type() {
+    assert_eq!(printed("print 1 + 2"), "3\n");
+    assert_eq!(printed("print 2.5 show \"red"), "2.5\nred\n");
+    assert_eq!(printed("print [a [b c] 1]"), "a [b c] 1\n");
+    assert_eq!(printed("show [a [b c] 1]"), "[a [b c] 1]\n");
+    assert_eq!(printed("type \"a type 1 print []"), "a1\n");
+}
+
+#[test]
+fn test_print_in_procedure_and_loop() {
+    let program = "to countdown :n\n if :n == 0 [ stop ]\n type :n type \", \n countdown :n - 1\nend\n\
+                   countdown 3 print \"go repeat 2 [ print repcount ]";
+    assert_eq!(printed(program), "3,2,1,go\n1\n2\n");
+}
+
+#[test]
+fn test_output_is_kept_between_runs() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str("print 1").unwrap();
+    interpreter.run_str("print 2").unwrap();
+    assert_eq!(interpreter.take_output(), "1\n2\n");
+    assert_eq!(interpreter.output(), "");
+
+    interpreter.run_str("print 3").unwrap();
+    interpreter.reset();
+    assert_eq!(interpreter.output(), "");
+}
+
+#[test]
+fn test_output_before_error() {
+    let mut interpreter = Interpreter::new(Config::default());
+    let result = interpreter.run_str("print 1 print :missing print 2");
+    assert_eq!(result, Err(LogoError::UnknownVariable("missing".to_string())));
+    assert_eq!(interpreter.output(), "1\n");
+}
+
+#[test]
+fn test_stdout_output_keeps_nothing() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.set_output(Output::Stdout);
+    interpreter.run_str("print 1").unwrap();
+    assert_eq!(interpreter.output(), "");
+}
+
+#[test]
+fn test_print_compiled() {
+    let code = "repeat 3 [ type repcount * 2 ] print 0.5 show 7";
+    let blocks = interpreter::parse(code).unwrap();
+    let mut vm = Interpreter::new(Config::default());
+    vm.run_compiled(&blocks).unwrap();
+    assert_eq!(vm.output(), printed(code));
+    assert_eq!(vm.output(), "2460.5\n7\n");
+}
+
+#[test]
+fn test_escaped_words() {
+    let program = "print \"|hello world| ; comment\nshow [|a b| c\\;d] print \"|x;y|\n\
+                   run [print \"|run me|] show (run [\"|a b|]) show (map [(list ? \"|a b|)] [1])";
+    assert_eq!(printed(program), "hello world\n[a b c;d]\nx;y\nrun me\na b\n[[1 a b]]\n");
+}
+
+#[test]
+fn test_nested_expressions() {
+    let program = "to f :a\n output (:a + 1) * 2\nend\nprint f 2\nprint 12 / ((f 0) + 2)";
+    assert_eq!(printed(program), "6\n3\n");
+}
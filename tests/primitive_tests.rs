@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lista7::evaluator::turtle::{Direction};
+use lista7::{Config, Interpreter, LogoError};
+
+#[test]
+fn test_native_procedure() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.register_procedure("stamplogo", 1, |turtle, args| {
+        turtle.go(Direction::Forward, args[0]);
+        turtle.go(Direction::Back, args[0]);
+        Ok(())
+    });
+
+    interpreter.run_str("to twice :n\n stamplogo :n stamplogo :n * 2\nend\ntwice 10").unwrap();
+
+    let lines = &interpreter.turtle().lines;
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[2].end_y, 300.0 - 20.0);
+}
+
+#[test]
+fn test_native_reporter() {
+    let readings = Rc::new(RefCell::new(vec![50.0, 20.0]));
+    let mut interpreter = Interpreter::new(Config::default());
+    let sensor_readings = readings.clone();
+    interpreter.register_reporter("sensor", 1, move |args| {
+        sensor_readings.borrow().get(args[0] as usize).copied().ok_or("no such sensor".to_string())
+    });
+
+    interpreter.run_str("fd (sensor 0) rt 90 fd (sensor 1) * 2").unwrap();
+
+    let turtle = interpreter.turtle();
+    assert_eq!(turtle.y, 250.0);
+    assert_eq!(turtle.x, 440.0);
+
+    readings.borrow_mut().clear();
+    assert_eq!(
+        interpreter.run_str("fd (sensor 0)"),
+        Err(LogoError::Native { name: "sensor".to_string(), message: "no such sensor".to_string() })
+    );
+}
+
+#[test]
+fn test_primitive_errors() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.register_procedure("stamplogo", 0, |_, _| Ok(()));
+    interpreter.register_reporter("sensor", 1, |_| Ok(1.0));
+
+    assert_eq!(
+        interpreter.run_str("(stamplogo 1)"),
+        Err(LogoError::WrongArity { name: "stamplogo".to_string(), expected: 0, got: 1 })
+    );
+    assert_eq!(
+        interpreter.run_str("fd (sensor)"),
+        Err(LogoError::WrongArity { name: "sensor".to_string(), expected: 1, got: 0 })
+    );
+    assert_eq!(interpreter.run_str("sensor 1"), Err(LogoError::NotACommand("sensor".to_string())));
+    assert_eq!(interpreter.run_str("fd (stamplogo)"), Err(LogoError::NoOutput("stamplogo".to_string())));
+    assert_eq!(interpreter.run_str("fd (nothing)"), Err(LogoError::UnknownProcedure("nothing".to_string())));
+}
+
+#[test]
+fn test_primitives_kept_after_reset() {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.register_reporter("two", 0, |_| Ok(2.0));
+
+    interpreter.reset();
+
+    interpreter.run_str("fd (two)").unwrap();
+    assert_eq!(interpreter.turtle().y, 298.0);
+}
@@ -0,0 +1,130 @@
+use lista7::{interpreter, Config, Interpreter, LogoError};
+
+// runs the program with the evaluator and on the stack machine, results have to be the same
+fn run_both(code: &str) -> Result<Interpreter, LogoError> {
+    let mut tree = Interpreter::new(Config::default());
+    let tree_result = tree.run_str(code);
+    let mut vm = Interpreter::new(Config::default());
+    let vm_result = vm.run_compiled(&interpreter::parse(code).unwrap());
+
+    assert_eq!(tree_result, vm_result, "{}", code);
+    assert_eq!(tree.output(), vm.output(), "{}", code);
+    assert_eq!(tree.turtle().lines, vm.turtle().lines, "{}", code);
+    tree_result.map(|_| tree)
+}
+
+fn printed(code: &str) -> String {
+    run_both(code).unwrap().output().to_string()
+}
+
+const FACT: &str = "to fact :n\n if :n < 2 [ output 1 ]\n output :n * (fact :n - 1)\nend\n";
+
+#[test]
+fn test_output_from_recursive_reporter() {
+    assert_eq!(printed(&format!("{}print (fact 5) print (fact 10)", FACT)), "120\n3628800\n");
+    // procedure called in an expression can draw too
+    let program = "to side :n\n fd :n rt 90\n op :n * 2\nend\nrepeat 2 [ fd (side 10) ]";
+    assert_eq!(run_both(program).unwrap().turtle().lines.len(), 4);
+}
+
+#[test]
+fn test_mutual_recursion() {
+    let program = "to iseven :n\n if :n == 0 [ output 1 ]\n output (isodd :n - 1)\nend\n\
+                   to isodd :n\n if :n == 0 [ output 0 ]\n output (iseven :n - 1)\nend\n\
+                   print (iseven 10) print (iseven 7) print (isodd 7)";
+    assert_eq!(printed(program), "1\n0\n1\n");
+
+    // procedures drawing each other's parts
+    let program = "to branch :size :depth\n if :depth == 0 [ stop ]\n fd :size leaf :size / 2 :depth - 1\n bk :size\nend\n\
+                   to leaf :size :depth\n lt 30 branch :size :depth\n rt 60 branch :size :depth\n lt 30\nend\n\
+                   branch 40 5";
+    let interpreter = run_both(program).unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 2 * (1 + 2 + 4 + 8 + 16));
+    assert_eq!(interpreter.turtle().angle, 270.0);
+}
+
+#[test]
+fn test_stop_deep_inside_loops() {
+    let program = "to f :n\n repeat 3 [ repeat 3 [ if repcount == :n [ stop ] fd 1 ] fd 100 ]\n fd 1000\nend\n\
+                   f 2 rt 90 fd 5 print repcount";
+    let interpreter = run_both(program).unwrap();
+    // stop ends the whole procedure, not only the loop; loops are left, so repcount is -1 again
+    assert_eq!(interpreter.turtle().lines.len(), 2);
+    assert_eq!(interpreter.output(), "-1\n");
+}
+
+#[test]
+fn test_output_deep_inside_loops() {
+    let program = "to root :n\n repeat :n [ repeat 1 [ if repcount * repcount > :n [ output repcount - 1 ] ] ]\n output :n\nend\n\
+                   to intsqrt :n\n repeat :n [ if repcount * repcount > :n [ output repcount - 1 ] ]\n output :n\nend\n\
+                   print (intsqrt 10) print (intsqrt 1) repeat 3 [ type (intsqrt repcount * 10) ] print repcount";
+    assert_eq!(printed(program), "3\n1\n345-1\n");
+}
+
+#[test]
+fn test_output_and_stop_errors() {
+    let test_cases = vec![
+        ("output 1", LogoError::NotInProcedure("output".to_string())),
+        ("to f :n\n output :n\nend\nf 1", LogoError::NotACommand("f".to_string())),
+        ("to f :n\n fd :n\nend\nfd (f 1)", LogoError::NoOutput("f".to_string())),
+        ("to f :n\n if :n > 0 [ stop ]\n output 1\nend\nfd (f 1)", LogoError::NoOutput("f".to_string())),
+        // command at the end of a reporter is not where its value comes from
+        ("to g :n\n output :n\nend\nto f :n\n g :n\nend\nfd (f 1)", LogoError::NotACommand("g".to_string())),
+        ("to g :n\n fd :n\nend\nto f :n\n g :n\nend\nfd (f 1)", LogoError::NoOutput("f".to_string())),
+    ];
+
+    for (code, expected) in test_cases {
+        assert_eq!(run_both(code).err(), Some(expected), "{}", code);
+    }
+}
+
+#[test]
+fn test_frames_are_removed_after_errors() {
+    let mut interpreter = Interpreter::new(Config::default());
+    let program = "to f :x\n repeat 2 [ g :x ]\nend\nto g :y\n fd :missing\nend\n";
+    assert_eq!(interpreter.run_str(&format!("{}f 1", program)), Err(LogoError::UnknownVariable("missing".to_string())));
+    assert_eq!(interpreter.run_str("print :x"), Err(LogoError::UnknownVariable("x".to_string())));
+    assert_eq!(interpreter.run_str("print :y"), Err(LogoError::UnknownVariable("y".to_string())));
+
+    // wrong number of arguments doesn't bind anything either
+    assert_eq!(
+        interpreter.run_str("(f 1 2)"),
+        Err(LogoError::WrongArity { name: "f".to_string(), expected: 1, got: 2 })
+    );
+    assert_eq!(
+        interpreter.run_str("print (fd1 1)"),
+        Err(LogoError::UnknownProcedure("fd1".to_string()))
+    );
+    interpreter.run_str("print repcount").unwrap();
+    assert_eq!(interpreter.output(), "-1\n");
+}
+
+#[test]
+fn test_output_in_templates_and_run() {
+    // output from a list run in a procedure ends the procedure
+    let program = "to pick2 :list\n foreach :list [ if ? > 2 [ output ? ] ]\n output 0\nend\n\
+                   print (pick2 [1 5 7]) print (pick2 [1]) \
+                   to double :x\n run [ output :x * 2 ]\nend\n\
+                   show (map \"double [1 2])";
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str(program).unwrap();
+    assert_eq!(interpreter.output(), "5\n0\n[2 4]\n");
+}
+
+#[test]
+fn test_negative_number_is_a_separate_input() {
+    let f = "to f :a :b\n output :a - :b\nend\n";
+    assert_eq!(printed(&format!("{}print f 1 -2\nprint f 5 - 1 -2\nprint f 5-1 2", f)), "3\n6\n2\n");
+
+    // print takes one input, so -1 is left without a command, like in Logo
+    let mut interpreter = Interpreter::new(Config::default());
+    assert!(matches!(interpreter.run_str("to g :x\n print :x -1\nend"), Err(LogoError::Parse(_))));
+    interpreter.run_str("to g :x\n print :x - 1\nend\ng 5").unwrap();
+    assert_eq!(interpreter.output(), "4\n");
+}
+
+#[test]
+fn test_bare_call_in_expression() {
+    let double = "to double :x\n output :x * 2\nend\n";
+    assert_eq!(printed(&format!("{}print 2 * double 5\nprint 1 + double double 3\nfd 10 - double 2", double)), "20\n13\n");
+}
@@ -1,4 +1,4 @@
-use lista7::parser::{unit::Unit, command::Command, block::Block, program::parse_program};
+use lista7::parser::{unit::Unit, command::Command, block::Block, program::{clean, parse_program, Arities}};
 
 use std::fs;
 
@@ -11,7 +11,7 @@ pub fn read_from_file(file_name: &str) -> String {
             return "error".to_string();
         }
     };
-    program_code.replace("\r\n\t", " ").trim().to_string()
+    clean(&program_code).trim().to_string()
 }
 
 
@@ -42,7 +42,7 @@ fn test_parse_program() {
         Block::Call("star".to_string(), vec![]),
     ];
 
-    match parse_program(program) {
+    match parse_program(program, &Arities::new()) {
         Ok((remaining, blocks)) => {
             assert!(remaining.is_empty(), "Remaining input should be empty.");
             assert_eq!(blocks, expected, "Parsed blocks do not match the expected structure.");
@@ -75,7 +75,7 @@ fn test_parse_star() {
         Block::Call("star".to_string(), vec![]),
     ];
 
-    match parse_program(program) {
+    match parse_program(program, &Arities::new()) {
         Ok((remaining, blocks)) => {
             assert!(remaining.is_empty(), "Remaining input should be empty.");
             assert_eq!(blocks, expected, "Parsed blocks do not match the expected structure.");
@@ -91,7 +91,7 @@ fn test_parse_star() {
 fn test_parse_squares() {
     let program = &read_from_file("squares");
 
-    match parse_program(program) {
+    match parse_program(program, &Arities::new()) {
         Ok((remaining, _)) => {
             assert!(remaining.is_empty(), "Remaining input should be empty.");
         }
@@ -104,7 +104,7 @@ fn test_parse_squares() {
 fn test_parse_race() {
     let program = &read_from_file("race");
 
-    match parse_program(program) {
+    match parse_program(program, &Arities::new()) {
         Ok((remaining, _)) => {
             assert!(remaining.is_empty(), "Remaining input should be empty.");
         }
@@ -117,7 +117,7 @@ fn test_parse_race() {
 fn test_parse_fern() {
     let program = &read_from_file("fern");
 
-    match parse_program(program) {
+    match parse_program(program, &Arities::new()) {
         Ok((remaining, _)) => {
             assert!(remaining.is_empty(), "Remaining input should be empty.");
         }
@@ -130,7 +130,7 @@ fn test_parse_fern() {
 fn test_parse_tree() {
     let program = &read_from_file("tree");
 
-    match parse_program(program) {
+    match parse_program(program, &Arities::new()) {
         Ok((remaining, _)) => {
             assert!(remaining.is_empty(), "Remaining input should be empty.");
         }
@@ -0,0 +1,70 @@
+use lista7::drawing::json::render_json;
+use lista7::evaluator::value::Value;
+use lista7::{interpreter, Config, Interpreter, LogoError};
+
+fn run_file(name: &str, seed: u64) -> String {
+    let mut interpreter = Interpreter::new(Config { seed: Some(seed), ..Config::default() });
+    interpreter.run_file(format!("codes/{}.txt", name)).unwrap();
+    render_json(interpreter.turtle())
+}
+
+#[test]
+fn test_same_seed_same_drawing() {
+    for name in ["squares", "race", "spiral"] {
+        assert_eq!(run_file(name, 7), run_file(name, 7), "{} differs for the same seed", name);
+    }
+}
+
+#[test]
+fn test_different_seed_different_drawing() {
+    assert_ne!(run_file("squares", 1), run_file("squares", 2));
+}
+
+#[test]
+fn test_seed_is_reported() {
+    let interpreter = Interpreter::new(Config { seed: Some(123), ..Config::default() });
+    assert_eq!(interpreter.seed(), 123);
+
+    // without seed in config, the chosen one can be used to repeat the drawing
+    let mut first = Interpreter::new(Config::default());
+    first.run_file("codes/squares.txt").unwrap();
+    assert_eq!(render_json(first.turtle()), run_file("squares", first.seed()));
+}
+
+#[test]
+fn test_rerandom() {
+    let mut interpreter = Interpreter::new(Config { seed: Some(5), ..Config::default() });
+
+    interpreter.run_str("repeat 10 [ fd random 100 ] rerandom repeat 10 [ fd random 100 ]").unwrap();
+    interpreter.run_str("rerandom 99 repeat 10 [ bk random 100 ] rerandom 99 repeat 10 [ bk random 100 ]").unwrap();
+
+    let lengths: Vec<f64> = interpreter.turtle().lines.iter().map(|l| (l.end_y - l.start_y).abs().round()).collect();
+    assert_eq!(lengths[0..10], lengths[10..20]);
+    assert_eq!(lengths[20..30], lengths[30..40]);
+    assert_ne!(lengths[0..10], lengths[20..30]);
+}
+
+#[test]
+fn test_rerandom_bad_seed() {
+    // negative and fractional seeds would be cut to other seeds, so they are errors, in the evaluator and on the VM
+    for seed in [-5.0, -9.0, 2.5] {
+        let code = format!("fd 10 rerandom {}", seed);
+        let expected = Err(LogoError::BadInput { name: "rerandom".to_string(), value: Value::Number(seed) });
+        let mut tree = Interpreter::new(Config::default());
+        assert_eq!(tree.run_str(&code), expected);
+        let mut vm = Interpreter::new(Config::default());
+        assert_eq!(vm.run_compiled(&interpreter::parse(&code).unwrap()), expected);
+    }
+}
+
+#[test]
+fn test_reset_starts_random_numbers_over() {
+    let mut interpreter = Interpreter::new(Config { seed: Some(5), ..Config::default() });
+
+    interpreter.run_file("codes/squares.txt").unwrap();
+    let first = render_json(interpreter.turtle());
+    interpreter.reset();
+    interpreter.run_file("codes/squares.txt").unwrap();
+
+    assert_eq!(render_json(interpreter.turtle()), first);
+}
@@ -0,0 +1,110 @@
+use lista7::evaluator::value::Value;
+use lista7::{Config, Interpreter, LogoError};
+
+fn run(code: &str) -> Result<Interpreter, LogoError> {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str(code)?;
+    Ok(interpreter)
+}
+
+fn printed(code: &str) -> String {
+    run(code).unwrap().output().to_string()
+}
+
+#[test]
+fn test_run_instruction_list() {
+    let square = run("run [repeat 4 [fd 10 rt 90]]").unwrap();
+    let expected = run("repeat 4 [fd 10 rt 90]").unwrap();
+    assert_eq!(square.turtle().lines, expected.turtle().lines);
+
+    // variables and parentheses in the list are evaluated when it runs, in the current scope
+    let program = "to path :points\n if (emptyp :points) [ stop ]\n run [fd (first :points) rt 90]\n path (bf :points)\nend\n\
+                   path [10 20 30]";
+    assert_eq!(run(program).unwrap().turtle().lines.len(), 3);
+    assert_eq!(printed("run [setcolor \"red print \"ok]"), "ok\n");
+}
+
+#[test]
+fn test_stop_in_run_stops_procedure() {
+    let program = "to f :n\n run [if :n > 1 [ stop ]]\n print :n\nend\nf 1 f 2 f 0";
+    assert_eq!(printed(program), "1\n0\n");
+}
+
+#[test]
+fn test_run_and_runresult_as_values() {
+    assert_eq!(printed("print (run [1 + 2])"), "3\n");
+    assert_eq!(printed("print (run [2 > 1]) print (run [\"a == \"b])"), "true\nfalse\n");
+    assert_eq!(printed("show (runresult [1 + 2])"), "[3]\n");
+    assert_eq!(printed("show (runresult [])"), "[]\n");
+    assert_eq!(printed("show (runresult [(list 1 [2])])"), "[[1 [2]]]\n");
+    assert_eq!(printed("repeat 2 [ print (run [repcount * 10]) ]"), "10\n20\n");
+}
+
+#[test]
+fn test_apply_and_invoke() {
+    let program = "to square :size\n repeat 4 [ fd :size rt 90 ]\nend\n";
+    let applied = run(&format!("{}apply \"square [10]", program)).unwrap();
+    let invoked = run(&format!("{}invoke \"square 10", program)).unwrap();
+    let called = run(&format!("{}square 10", program)).unwrap();
+    assert_eq!(applied.turtle().lines, called.turtle().lines);
+    assert_eq!(invoked.turtle().lines, called.turtle().lines);
+
+    // built-in commands and reporters
+    assert_eq!(run("apply \"fd [10] invoke \"rt 90 apply \"setcolor [red] fd 5").unwrap().turtle().lines.len(), 2);
+    assert_eq!(printed("print (apply \"item [2 [a b c]])"), "b\n");
+    assert_eq!(printed("show (invoke \"list 1 2 3)"), "[1 2 3]\n");
+}
+
+#[test]
+fn test_lsystem_written_in_logo() {
+    // koch snowflake: F -> F + F - - F + F
+    let program = "to rule :symbol :depth\n\
+                   if :symbol == \"F [ if :depth == 0 [ fd 10 ] if :depth > 0 [ walk [F + F - - F + F] :depth - 1 ] ]\n\
+                   if :symbol == \"+ [ run [lt 60] ]\n\
+                   if :symbol == \"- [ run [rt 60] ]\n\
+                   end\n\
+                   to walk :symbols :depth\n\
+                   if (emptyp :symbols) [ stop ]\n\
+                   rule (first :symbols) :depth\n\
+                   walk (bf :symbols) :depth\n\
+                   end\n\
+                   walk [F - - F - - F] 2";
+    let interpreter = run(program).unwrap();
+    let lines = &interpreter.turtle().lines;
+    assert_eq!(lines.len(), 3 * 4 * 4);
+
+    // closed curve
+    let (first, last) = (&lines[0], &lines[lines.len() - 1]);
+    assert!((first.start_x - last.end_x).abs() < 1e-6);
+    assert!((first.start_y - last.end_y).abs() < 1e-6);
+}
+
+#[test]
+fn test_run_errors() {
+    assert_eq!(run("run 5").err(), Some(LogoError::BadInput { name: "run".to_string(), value: Value::Number(5.0) }));
+    assert_eq!(run("runresult [1]").err(), Some(LogoError::NotACommand("runresult".to_string())));
+    assert_eq!(
+        run("(apply \"fd 1 2)").err(),
+        Some(LogoError::WrongArity { name: "apply".to_string(), expected: 2, got: 3 })
+    );
+    assert_eq!(run("apply \"nothing [1]").err(), Some(LogoError::UnknownProcedure("nothing".to_string())));
+    assert!(matches!(run("print (run [fd 10])").err(), Some(LogoError::Parse(_))));
+}
+
+#[test]
+fn test_list_written_back_as_code() {
+    let word = |w: &str| Value::Word(w.to_string());
+    let list = Value::List(vec![
+        word("print"),
+        word("\"a b"),
+        word("("),
+        Value::List(vec![word("[x]"), word(""), Value::Number(1.5)]),
+        word(")"),
+    ]);
+    assert_eq!(list.source(), "print \"a\\ b ( [\\[x\\] || 1.5] )");
+
+    // words from the inputs of apply keep their spaces
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str("to greet :name\n print :name\nend\napply \"greet [|John Smith|] apply \"print [|a;b|]").unwrap();
+    assert_eq!(interpreter.output(), "John Smith\na;b\n");
+}
@@ -0,0 +1,76 @@
+use lista7::evaluator::limits::DEFAULT_MAX_DEPTH;
+use lista7::{interpreter, Config, Interpreter, LogoError};
+
+const SPIRAL: &str = "to drawspiral :size :n\n if :n == 0 [ stop ]\n fd :size rt 1\n drawspiral :size + 0.001 :n - 1\nend\n";
+
+fn run_both(code: &str) -> (Interpreter, Interpreter) {
+    let mut tree = Interpreter::new(Config::default());
+    tree.run_str(code).unwrap();
+    let mut vm = Interpreter::new(Config::default());
+    vm.run_compiled(&interpreter::parse(code).unwrap()).unwrap();
+    (tree, vm)
+}
+
+#[test]
+fn test_million_deep_tail_recursion() {
+    // default depth limit is 100, tail calls don't count; pen is up, so a million lines are not kept
+    let (tree, vm) = run_both(&format!("{}pu drawspiral 1 1000000", SPIRAL));
+
+    for interpreter in [&tree, &vm] {
+        assert_eq!(interpreter.turtle().angle, 270.0 + 1000000.0);
+    }
+    assert_eq!(vm.turtle().x, tree.turtle().x);
+    assert_eq!(vm.turtle().y, tree.turtle().y);
+}
+
+#[test]
+fn test_drawn_tail_recursive_spiral() {
+    let (tree, vm) = run_both(&format!("{}drawspiral 1 10000", SPIRAL));
+
+    assert_eq!(tree.turtle().lines.len(), 10000);
+    assert_eq!(vm.turtle().lines, tree.turtle().lines);
+}
+
+#[test]
+fn test_tail_call_in_if_and_mutual_recursion() {
+    let program = "to ping :n\n fd 1\n if :n > 0 [ pong :n - 1 ]\nend\n\
+                   to pong :n\n rt 90\n ping :n\nend\nping 5000";
+    let (tree, vm) = run_both(program);
+
+    assert_eq!(tree.turtle().lines.len(), 5001);
+    assert_eq!(vm.turtle().lines, tree.turtle().lines);
+}
+
+#[test]
+fn test_call_not_in_tail_position_is_limited() {
+    // fd after the call has to be done after it returns
+    let program = "to down :n\n if :n == 0 [ stop ]\n down :n - 1\n fd 1\nend\ndown 20000";
+    let mut tree = Interpreter::new(Config::default());
+    let mut vm = Interpreter::new(Config::default());
+
+    assert_eq!(tree.run_str(program), Err(LogoError::DepthLimit(DEFAULT_MAX_DEPTH)));
+    assert_eq!(vm.run_compiled(&interpreter::parse(program).unwrap()), Err(LogoError::DepthLimit(DEFAULT_MAX_DEPTH)));
+}
+
+#[test]
+fn test_deep_recursion_not_in_tail_position() {
+    // much deeper than the 2 MB stack of the test thread would allow without growing it
+    let program = "to down :n\n if :n > 0 [ down :n - 1 fd 1 ]\nend\nto depth :n\n if :n == 0 [ output 0 ]\n output 1 + (depth :n - 1)\nend\n\
+                   down 5000\nprint depth 5000";
+    let (tree, vm) = run_both(program);
+
+    assert_eq!(tree.turtle().lines.len(), 5000);
+    assert_eq!(tree.output(), "5000\n");
+    assert_eq!(vm.turtle().lines, tree.turtle().lines);
+    assert_eq!(vm.output(), tree.output());
+}
+
+#[test]
+fn test_tail_call_keeps_dynamic_scope() {
+    // :size of the caller is visible in the procedure called from it, also in the tail one
+    let program = "to outer :size\n inner 2\nend\nto inner :n\n fd :size * :n\nend\nouter 10";
+    let (tree, vm) = run_both(program);
+
+    assert_eq!(tree.turtle().y, 300.0 - 20.0);
+    assert_eq!(vm.turtle().lines, tree.turtle().lines);
+}
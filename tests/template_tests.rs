@@ -0,0 +1,94 @@
+use lista7::evaluator::value::Value;
+use lista7::{Config, Interpreter, LogoError};
+
+fn run(code: &str) -> Result<Interpreter, LogoError> {
+    let mut interpreter = Interpreter::new(Config::default());
+    interpreter.run_str(code)?;
+    Ok(interpreter)
+}
+
+fn printed(code: &str) -> String {
+    run(code).unwrap().output().to_string()
+}
+
+#[test]
+fn test_map_filter_reduce() {
+    let test_cases = vec![
+        ("show (map [? * 2] [1 2 3])", "[2 4 6]\n"),
+        ("show (map \"first [[1 2] [3 4]])", "[1 3]\n"),
+        ("show (map [(list ? ?)] [a b])", "[[a a] [b b]]\n"),
+        ("show (filter [? > 1] [3 1 2])", "[3 2]\n"),
+        ("show (filter \"emptyp [[] [1] []])", "[[] []]\n"),
+        ("print (reduce [?1 + ?2] [1 2 3 4])", "10\n"),
+        ("print (reduce [?1 - ?2] [1 2 3])", "2\n"),
+        ("print (reduce [?1 + ?2] [5])", "5\n"),
+        ("show (map [? * 2] [])", "[]\n"),
+        ("show (apply [?1 * ?2] [3 4])", "12\n"),
+        // ? in a nested template is its own input
+        ("show (map [(map [? * 2] ?)] [[1 2] [3]])", "[[2 4] [6]]\n"),
+        ("show (map [(filter [? > 1] ?)] [[1 2] [3]])", "[[2] [3]]\n"),
+        ("show (map [(apply [? + 1] (list ?))] [1 2])", "[2 3]\n"),
+    ];
+
+    for (code, expected) in test_cases {
+        assert_eq!(printed(code), expected, "{}", code);
+    }
+}
+
+#[test]
+fn test_templates_see_variables_of_caller() {
+    let program = "to scale :list :factor\n show (map [? * :factor] :list)\nend\nscale [1 2] 10";
+    assert_eq!(printed(program), "[10 20]\n");
+}
+
+#[test]
+fn test_foreach() {
+    // polygons from the list of numbers of sides
+    let interpreter = run("foreach [3 4 5] [repeat ? [fd 10 rt 360 / ?]]").unwrap();
+    assert_eq!(interpreter.turtle().lines.len(), 3 + 4 + 5);
+
+    assert_eq!(printed("foreach [a b c] [type ? type #] print []"), "a1b2c3\n");
+    assert_eq!(printed("foreach (map [? * ?] [1 2 3]) \"print"), "1\n4\n9\n");
+}
+
+#[test]
+fn test_nested_foreach() {
+    // lists after foreach's data are its template, other nested lists (like bodies of repeat) are filled
+    assert_eq!(printed("foreach [1 2] [foreach [a b] [type ?] repeat ? [type ?]]"), "ab1ab22");
+}
+
+#[test]
+fn test_polygon_from_vertex_list() {
+    // turns between the sides, computed from the directions of the sides
+    let program = "to polygon :sides\n\
+                   foreach :sides [fd (first ?) rt (last ?)]\n\
+                   end\n\
+                   polygon (map [(list 50 ?)] [90 90 90 90])";
+    let polygon = run(program).unwrap();
+    let square = run("repeat 4 [fd 50 rt 90]").unwrap();
+    assert_eq!(polygon.turtle().lines, square.turtle().lines);
+}
+
+#[test]
+fn test_stop_in_foreach_stops_procedure() {
+    let program = "to upto :list :limit\n foreach :list [if ? > :limit [ stop ] type ?]\n print \"end\nend\n\
+                   upto [1 2 3 4] 2 print [] upto [1 2] 5";
+    assert_eq!(printed(program), "12\n12end\n");
+}
+
+#[test]
+fn test_template_errors() {
+    assert_eq!(
+        run("print (reduce [?1 + ?2] [])").err(),
+        Some(LogoError::BadInput { name: "reduce".to_string(), value: Value::List(vec![]) })
+    );
+    assert_eq!(
+        run("print (filter [? * 2] [1])").err(),
+        Some(LogoError::BadInput { name: "filter".to_string(), value: Value::Number(2.0) })
+    );
+    assert_eq!(run("map [? * 2] [1]").err(), Some(LogoError::NotACommand("map".to_string())));
+    assert_eq!(
+        run("foreach [1]").err(),
+        Some(LogoError::WrongArity { name: "foreach".to_string(), expected: 2, got: 1 })
+    );
+}
@@ -0,0 +1,44 @@
+use lista7::evaluator::turtle::{Direction, Turtle};
+use lista7::drawing::term::render_term;
+
+#[test]
+fn test_term_size() {
+    let turtle = Turtle::new(800.0, 600.0);
+    let output = render_term(&turtle, 40, 10, false);
+
+    let rows: Vec<&str> = output.lines().collect();
+    assert_eq!(rows.len(), 10);
+    assert!(rows.iter().all(|row| row.chars().count() == 40));
+}
+
+#[test]
+fn test_term_empty_image() {
+    let turtle = Turtle::new(800.0, 600.0);
+    let output = render_term(&turtle, 4, 2, false);
+
+    assert_eq!(output, "\u{2800}\u{2800}\u{2800}\u{2800}\n\u{2800}\u{2800}\u{2800}\u{2800}\n");
+}
+
+#[test]
+fn test_term_vertical_line() {
+    // 8x8 image on 4x2 cells (8x8 dots), line at x = 0 going through the whole height
+    let mut turtle = Turtle::new(8.0, 8.0);
+    turtle.x = 0.0;
+    turtle.y = 7.0;
+    turtle.go(Direction::Forward, 7.0);
+    let output = render_term(&turtle, 4, 2, false);
+
+    // left column of dots in the first cell of every row
+    assert_eq!(output, "\u{2847}\u{2800}\u{2800}\u{2800}\n\u{2847}\u{2800}\u{2800}\u{2800}\n");
+}
+
+#[test]
+fn test_term_colors() {
+    let mut turtle = Turtle::new(8.0, 8.0);
+    turtle.change_color("red".to_string());
+    turtle.go(Direction::Forward, 2.0);
+    let output = render_term(&turtle, 4, 2, true);
+
+    assert!(output.contains("\x1b[38;2;255;0;0m"));
+    assert!(output.contains("\x1b[0m"));
+}
@@ -1,4 +1,6 @@
-use lista7::evaluator::turtle::Turtle;
+#![allow(clippy::bool_assert_comparison)]
+
+use lista7::evaluator::turtle::{Direction, Pen, Turn, Turtle};
 
 #[test]
 fn test_turtle_creation() {
@@ -13,10 +15,10 @@ fn test_turtle_creation() {
 #[test]
 fn test_rotate_right() {
     let mut turtle = Turtle::new(800.0, 600.0);
-    turtle.rotate("right", 90.0);
+    turtle.rotate(Turn::Right, 90.0);
     assert_eq!(turtle.angle, 360.0);
 
-    turtle.go("forward", 100.0);
+    turtle.go(Direction::Forward, 100.0);
     let line = &turtle.lines[0];
     assert_eq!(line.start_x, 400.0);
     assert_eq!(line.start_y, 300.0);
@@ -27,10 +29,10 @@ fn test_rotate_right() {
 #[test]
 fn test_rotate_left() {
     let mut turtle = Turtle::new(800.0, 600.0);
-    turtle.rotate("left", 90.0);
+    turtle.rotate(Turn::Left, 90.0);
     assert_eq!(turtle.angle, 180.0);
 
-    turtle.go("forward", 100.0);
+    turtle.go(Direction::Forward, 100.0);
     let line = &turtle.lines[0];
     assert_eq!(line.start_x, 400.0);
     assert_eq!(line.start_y, 300.0);
@@ -41,7 +43,7 @@ fn test_rotate_left() {
 #[test]
 fn test_move_forward() {
     let mut turtle = Turtle::new(800.0, 600.0);
-    turtle.go("forward", 100.0);
+    turtle.go(Direction::Forward, 100.0);
 
     assert_eq!(turtle.x, 400.0); 
     assert_eq!(turtle.y, 300.0 - 100.0);
@@ -57,7 +59,7 @@ fn test_move_forward() {
 #[test]
 fn test_move_back() {
     let mut turtle = Turtle::new(800.0, 600.0);
-    turtle.go("back", 50.0);
+    turtle.go(Direction::Back, 50.0);
 
     assert_eq!(turtle.x, 400.0); 
     assert_eq!(turtle.y, 300.0 + 50.0);
@@ -74,17 +76,17 @@ fn test_move_back() {
 fn test_pen_up_and_down() {
     let mut turtle = Turtle::new(800.0, 600.0);
     assert_eq!(turtle.pen_down, true);
-    turtle.move_pen("up");
+    turtle.move_pen(Pen::Up);
     assert_eq!(turtle.pen_down, false);
-    turtle.move_pen("down");
+    turtle.move_pen(Pen::Down);
     assert_eq!(turtle.pen_down, true);
 }
 
 #[test]
 fn test_clear_screen() {
     let mut turtle = Turtle::new(800.0, 600.0);
-    turtle.go("back", 50.0);
-    turtle.go("forward", 250.0);
+    turtle.go(Direction::Back, 50.0);
+    turtle.go(Direction::Forward, 250.0);
 
     assert_eq!(turtle.lines.len(), 2);
 
@@ -92,3 +94,44 @@ fn test_clear_screen() {
 
     assert_eq!(turtle.lines.len(), 0);
 }
+
+#[test]
+fn test_set_turtle() {
+    let mut turtle = Turtle::new(800.0, 600.0);
+    turtle.go(Direction::Forward, 100.0);
+    turtle.change_color("red".to_string());
+
+    // new turtle starts in the center with default pen
+    turtle.set_turtle(2);
+    assert_eq!(turtle.id, 2);
+    assert_eq!((turtle.x, turtle.y), (400.0, 300.0));
+    assert_eq!(turtle.pen_color, "black");
+    turtle.rotate(Turn::Right, 90.0);
+    turtle.go(Direction::Forward, 50.0);
+
+    // first turtle is restored where it stopped
+    turtle.set_turtle(0);
+    assert_eq!((turtle.x, turtle.y), (400.0, 200.0));
+    assert_eq!(turtle.angle, 270.0);
+    assert_eq!(turtle.pen_color, "red");
+
+    turtle.set_turtle(2);
+    assert_eq!((turtle.x, turtle.y), (450.0, 300.0));
+
+    assert_eq!(turtle.lines.len(), 2);
+    assert_eq!(turtle.lines[0].turtle, 0);
+    assert_eq!(turtle.lines[1].turtle, 2);
+}
+
+#[test]
+fn test_wait() {
+    let mut turtle = Turtle::new(800.0, 600.0);
+    turtle.go(Direction::Forward, 10.0);
+    turtle.wait(2.0);
+    turtle.go(Direction::Forward, 10.0);
+    turtle.wait(-5.0);
+
+    assert_eq!(turtle.time, 2.0);
+    assert_eq!(turtle.lines[0].time, 0.0);
+    assert_eq!(turtle.lines[1].time, 2.0);
+}
@@ -1,13 +1,21 @@
+use lista7::evaluator::value::Value;
 use lista7::evaluator::eval::eval_unit;
 use lista7::evaluator::environment::Env;
-use lista7::parser::unit::Unit;
+use lista7::evaluator::turtle::Turtle;
+use lista7::parser::unit::{ArithOp, Unit};
+use lista7::LogoError;
+
+// units don't draw, unless they call user procedures
+fn eval(unit: &Unit, env: &mut Env) -> Result<Value, LogoError> {
+    eval_unit(unit, &mut Turtle::new(800.0, 600.0), env)
+}
 
 #[test]
 fn test_eval_unit_val() {
     let unit = Unit::Val(42.0);
-    let env = Env::new();
+    let mut env = Env::new();
     
-    let result = eval_unit(unit, env);
+    let result = eval(&unit, &mut env).unwrap().number().unwrap();
     
     assert_eq!(result, 42.0); 
 }
@@ -15,37 +23,37 @@ fn test_eval_unit_val() {
 #[test]
 fn test_eval_unit_var() {
     let mut env = Env::new();
-    env.set_var("x".to_string(), 10.0);
+    env.set_var("x".to_string(), Value::Number(10.0));
     let unit = Unit::Var("x".to_string());
     
-    let result = eval_unit(unit, env);
+    let result = eval(&unit, &mut env).unwrap().number().unwrap();
     
     assert_eq!(result, 10.0); 
 }
 
 #[test]
 fn test_eval_unit_var_not_found() {
-    let env = Env::new();
+    let mut env = Env::new();
     let unit = Unit::Var("y".to_string());
     
-    let result = eval_unit(unit, env);
+    let result = eval(&unit, &mut env);
     
-    assert_eq!(result, 0.0); 
+    assert_eq!(result, Err(LogoError::UnknownVariable("y".to_string()))); 
 }
 
 #[test]
 fn test_eval_unit_exp_add() {
     let mut env = Env::new();
-    env.set_var("x".to_string(), 10.0);
-    env.set_var("y".to_string(), 5.0);
+    env.set_var("x".to_string(), Value::Number(10.0));
+    env.set_var("y".to_string(), Value::Number(5.0));
     
     let expr = Unit::Exp(
         Box::new(Unit::Var("x".to_string())),
-        "+".to_string(),
+        ArithOp::Add,
         Box::new(Unit::Var("y".to_string())),
     );
     
-    let result = eval_unit(expr, env);
+    let result = eval(&expr, &mut env).unwrap().number().unwrap();
     
     assert_eq!(result, 15.0);
 }
@@ -53,16 +61,16 @@ fn test_eval_unit_exp_add() {
 #[test]
 fn test_eval_unit_exp_subtract() {
     let mut env = Env::new();
-    env.set_var("x".to_string(), 10.0);
-    env.set_var("y".to_string(), 5.0);
+    env.set_var("x".to_string(), Value::Number(10.0));
+    env.set_var("y".to_string(), Value::Number(5.0));
     
     let expr = Unit::Exp(
         Box::new(Unit::Var("x".to_string())),
-        "-".to_string(),
+        ArithOp::Sub,
         Box::new(Unit::Var("y".to_string())),
     );
     
-    let result = eval_unit(expr, env);
+    let result = eval(&expr, &mut env).unwrap().number().unwrap();
     
     assert_eq!(result, 5.0); 
 }
@@ -70,16 +78,16 @@ fn test_eval_unit_exp_subtract() {
 #[test]
 fn test_eval_unit_exp_multiply() {
     let mut env = Env::new();
-    env.set_var("x".to_string(), 10.0);
-    env.set_var("y".to_string(), 5.0);
+    env.set_var("x".to_string(), Value::Number(10.0));
+    env.set_var("y".to_string(), Value::Number(5.0));
     
     let expr = Unit::Exp(
         Box::new(Unit::Var("x".to_string())),
-        "*".to_string(),
+        ArithOp::Mul,
         Box::new(Unit::Var("y".to_string())),
     );
     
-    let result = eval_unit(expr, env);
+    let result = eval(&expr, &mut env).unwrap().number().unwrap();
     
     assert_eq!(result, 50.0); 
 }
@@ -87,34 +95,74 @@ fn test_eval_unit_exp_multiply() {
 #[test]
 fn test_eval_unit_exp_divide() {
     let mut env = Env::new();
-    env.set_var("x".to_string(), 10.0);
-    env.set_var("y".to_string(), 5.0);
+    env.set_var("x".to_string(), Value::Number(10.0));
+    env.set_var("y".to_string(), Value::Number(5.0));
     
     let expr = Unit::Exp(
         Box::new(Unit::Var("x".to_string())),
-        "/".to_string(),
+        ArithOp::Div,
         Box::new(Unit::Var("y".to_string())),
     );
     
-    let result = eval_unit(expr, env);
+    let result = eval(&expr, &mut env).unwrap().number().unwrap();
     
     assert_eq!(result, 2.0);
 }
 
 #[test]
-fn test_eval_unit_exp_unknown_operator() {
-    let mut env = Env::new();
-    env.set_var("x".to_string(), 10.0);
-    env.set_var("y".to_string(), 5.0);
-    
-    let expr = Unit::Exp(
-        Box::new(Unit::Var("x".to_string())),
-        "unknown".to_string(),
-        Box::new(Unit::Var("y".to_string())),
-    );
-    
-    let result = eval_unit(expr, env);
-    
-    assert_eq!(result, 0.0); 
+fn test_eval_random() {
+    let mut env = Env::with_seed(1);
+    let mut seen = [false; 3];
+
+    for _ in 0..100 {
+        let result = eval(&Unit::Random(Box::new(Unit::Val(3.0))), &mut env).unwrap().number().unwrap();
+        seen[result as usize] = true;
+    }
+
+    // 0 and the bound - 1 are possible too
+    assert_eq!(seen, [true, true, true]);
+    assert_eq!(eval(&Unit::Random(Box::new(Unit::Val(1.0))), &mut env).unwrap(), Value::Number(0.0));
 }
 
+#[test]
+fn test_eval_random_range() {
+    let mut env = Env::with_seed(1);
+    let unit = Unit::RandomRange(Box::new(Unit::Val(-2.0)), Box::new(Unit::Val(2.0)));
+    let mut seen = [false; 5];
+
+    for _ in 0..100 {
+        let result = eval(&unit, &mut env).unwrap().number().unwrap();
+        seen[(result + 2.0) as usize] = true;
+    }
+
+    assert_eq!(seen, [true; 5]);
+}
+
+#[test]
+fn test_eval_random_float() {
+    let mut env = Env::with_seed(1);
+
+    for _ in 0..100 {
+        let result = eval(&Unit::RandomFloat(Box::new(Unit::Val(0.5))), &mut env).unwrap().number().unwrap();
+        assert!((0.0..0.5).contains(&result));
+    }
+}
+
+#[test]
+fn test_eval_random_invalid_bounds() {
+    let mut env = Env::with_seed(1);
+    let bad_input = |name: &str, value: f64| Err(LogoError::BadInput { name: name.to_string(), value: Value::Number(value) });
+
+    for bound in [0.0, -5.0, 2.5, f64::INFINITY] {
+        assert_eq!(eval(&Unit::Random(Box::new(Unit::Val(bound))), &mut env), bad_input("random", bound));
+    }
+    assert_eq!(
+        eval(&Unit::RandomRange(Box::new(Unit::Val(5.0)), Box::new(Unit::Val(1.0))), &mut env),
+        bad_input("random", 1.0)
+    );
+    assert_eq!(
+        eval(&Unit::RandomRange(Box::new(Unit::Val(0.5)), Box::new(Unit::Val(1.0))), &mut env),
+        bad_input("random", 0.5)
+    );
+    assert_eq!(eval(&Unit::RandomFloat(Box::new(Unit::Val(0.0))), &mut env), bad_input("randomfloat", 0.0));
+}
@@ -1,4 +1,5 @@
-use lista7::parser::unit::{Unit, parse_unit};
+use lista7::parser::unit::{ArithOp, Unit, parse_unit};
+use lista7::parser::program::Arities;
 
 #[test]
 fn test_parse_val() {
@@ -7,15 +8,23 @@ fn test_parse_val() {
         ("67", Unit::Val(67.0)),
         ("-67", Unit::Val(-67.0)),
         ("-67.96", Unit::Val(-67.96)),
+        ("1e300", Unit::Val(1e300)),
+        ("2.5E-3", Unit::Val(0.0025)),
+        ("-1e+2", Unit::Val(-100.0)),
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_unit(input);
+        let result = parse_unit(input, &Arities::new());
         match result {
             Ok((_, unit)) => assert_eq!(unit, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
         }
     }
+    // number is not split into a number and a name
+    for input in ["1e", "10abc", "1e400", "2.5e"] {
+        assert!(parse_unit(input, &Arities::new()).is_err(), "{}", input);
+    }
+    assert_eq!(parse_unit("random 1e300", &Arities::new()), Ok(("", Unit::Random(Box::new(Unit::Val(1e300))))));
 }
 
 #[test]
@@ -26,7 +35,7 @@ fn test_parse_var() {
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_unit(input);
+        let result = parse_unit(input, &Arities::new());
         match result {
             Ok((_, unit)) => assert_eq!(unit, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
@@ -39,10 +48,13 @@ fn test_parse_random() {
     let test_cases = vec![
         ("random 200", Unit::Random(Box::new(Unit::Val(200.0)))),
         ("Rm :times", Unit::Random(Box::new(Unit::Var("times".to_string())))),
+        ("(random 6)", Unit::Random(Box::new(Unit::Val(6.0)))),
+        ("( random 1 :n )", Unit::RandomRange(Box::new(Unit::Val(1.0)), Box::new(Unit::Var("n".to_string())))),
+        ("randomfloat 0.5", Unit::RandomFloat(Box::new(Unit::Val(0.5)))),
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_unit(input);
+        let result = parse_unit(input, &Arities::new());
         match result {
             Ok((_, unit)) => assert_eq!(unit, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
@@ -55,31 +67,209 @@ fn test_parse_expression() {
     let test_cases = vec![
         ("9.5 / -3", Unit::Exp(
             Box::new(Unit::Val(9.5)),
-            "/".to_string(),
+            ArithOp::Div,
             Box::new(Unit::Val(-3.0))
         )),
         (":size * 3", Unit::Exp(
             Box::new(Unit::Var("size".to_string())),
-            "*".to_string(),
+            ArithOp::Mul,
             Box::new(Unit::Val(3.0))
         )),
         ("-2.14 + :times", Unit::Exp(
             Box::new(Unit::Val(-2.14)),
-            "+".to_string(),
+            ArithOp::Add,
             Box::new(Unit::Var("times".to_string()))
         )),
         (":iterations - :times", Unit::Exp(
             Box::new(Unit::Var("iterations".to_string())),
-            "-".to_string(),
+            ArithOp::Sub,
             Box::new(Unit::Var("times".to_string()))
         )),
     ];
 
     for (input, expected) in test_cases {
-        let result = parse_unit(input);
+        let result = parse_unit(input, &Arities::new());
+        match result {
+            Ok((_, unit)) => assert_eq!(unit, expected),
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_negative_number_after_unit() {
+    let var = || Box::new(Unit::Var("x".to_string()));
+    // minus with a space before it and none after starts the next unit
+    let test_cases = vec![
+        (":x -1", Unit::Var("x".to_string()), " -1"),
+        ("1 -2", Unit::Val(1.0), " -2"),
+        (":x - 1", Unit::Exp(var(), ArithOp::Sub, Box::new(Unit::Val(1.0))), ""),
+        (":x-1", Unit::Exp(var(), ArithOp::Sub, Box::new(Unit::Val(1.0))), ""),
+        (":x - -1", Unit::Exp(var(), ArithOp::Sub, Box::new(Unit::Val(-1.0))), ""),
+        (":x -:x", Unit::Exp(var(), ArithOp::Sub, var()), ""),
+    ];
+
+    for (input, expected, rest) in test_cases {
+        match parse_unit(input, &Arities::new()) {
+            Ok((remaining, unit)) => {
+                assert_eq!(unit, expected, "{}", input);
+                assert_eq!(remaining, rest, "{}", input);
+            }
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_call() {
+    let test_cases = vec![
+        ("(sensor)", Unit::Call("sensor".to_string(), vec![])),
+        ("( sensor 1 :x )", Unit::Call("sensor".to_string(), vec![Unit::Val(1.0), Unit::Var("x".to_string())])),
+        ("(sensor 1) * 2", Unit::Exp(
+            Box::new(Unit::Call("sensor".to_string(), vec![Unit::Val(1.0)])),
+            ArithOp::Mul,
+            Box::new(Unit::Val(2.0)),
+        )),
+    ];
+
+    for (input, expected) in test_cases {
+        let result = parse_unit(input, &Arities::new());
+        match result {
+            Ok((_, unit)) => assert_eq!(unit, expected),
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_bare_call_in_expression() {
+    let arities = Arities::from([("double".to_string(), 1)]);
+    let double = |arg: Unit| Box::new(Unit::Call("double".to_string(), vec![arg]));
+    let test_cases = vec![
+        ("2 * double 5", Unit::Exp(Box::new(Unit::Val(2.0)), ArithOp::Mul, double(Unit::Val(5.0)))),
+        (":size - double :size", Unit::Exp(
+            Box::new(Unit::Var("size".to_string())),
+            ArithOp::Sub,
+            double(Unit::Var("size".to_string())),
+        )),
+        // inputs of the call take the rest of the expression, like in Logo
+        ("double 5 * 2", *double(Unit::Exp(Box::new(Unit::Val(5.0)), ArithOp::Mul, Box::new(Unit::Val(2.0))))),
+    ];
+
+    for (input, expected) in test_cases {
+        match parse_unit(input, &arities) {
+            Ok(("", unit)) => assert_eq!(unit, expected),
+            _ => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_repcount() {
+    let test_cases = vec![
+        ("repcount", Unit::RepCount),
+        ("#", Unit::RepCount),
+        ("repcount * 10", Unit::Exp(
+            Box::new(Unit::RepCount),
+            ArithOp::Mul,
+            Box::new(Unit::Val(10.0)),
+        )),
+    ];
+
+    for (input, expected) in test_cases {
+        let result = parse_unit(input, &Arities::new());
+        match result {
+            Ok((_, unit)) => assert_eq!(unit, expected),
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_unknown_operator() {
+    // % is not an operator, so only the number is parsed
+    let result = parse_unit("7 % 2", &Arities::new());
+
+    assert_eq!(result, Ok((" % 2", Unit::Val(7.0))));
+}
+
+#[test]
+fn test_parse_word_and_list() {
+    let word = |w: &str| Unit::Word(w.to_string());
+    let test_cases = vec![
+        ("\"red", word("red")),
+        ("\"a.b", word("a.b")),
+        ("[]", Unit::List(vec![])),
+        ("[1 2 3]", Unit::List(vec![Unit::Val(1.0), Unit::Val(2.0), Unit::Val(3.0)])),
+        ("[ red [0 -10] ]", Unit::List(vec![word("red"), Unit::List(vec![Unit::Val(0.0), Unit::Val(-10.0)])])),
+    ];
+
+    for (input, expected) in test_cases {
+        let result = parse_unit(input, &Arities::new());
+        match result {
+            Ok((_, unit)) => assert_eq!(unit, expected),
+            Err(_) => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_escaped_words() {
+    let word = |w: &str| Unit::Word(w.to_string());
+    let test_cases = vec![
+        ("\"|light blue|", word("light blue")),
+        ("\"hello\\ world", word("hello world")),
+        ("\"a|[b]|c", word("a[b]c")),
+        ("\"\\[1\\]", word("[1]")),
+        ("\"|;|", word(";")),
+        ("\"\\|", word("|")),
+        ("\"\\\\", word("\\")),
+        ("\"", word("")),
+        ("\"||", word("")),
+        ("[|light blue| red\\ wine |10| 10]", Unit::List(vec![word("light blue"), word("red wine"), word("10"), Unit::Val(10.0)])),
+        ("[\"|a b| [\\]]]", Unit::List(vec![word("\"a b"), Unit::List(vec![word("]")])])),
+    ];
+
+    for (input, expected) in test_cases {
+        let result = parse_unit(input, &Arities::new());
+        match result {
+            Ok(("", unit)) => assert_eq!(unit, expected, "{}", input),
+            _ => panic!("Błąd parsowania dla '{}'", input),
+        }
+    }
+}
+
+#[test]
+fn test_parse_unclosed_bar() {
+    let test_cases = vec!["\"|light blue", "[a |b]", "\"a\\"];
+
+    for input in test_cases {
+        assert!(!matches!(parse_unit(input, &Arities::new()), Ok(("", _))), "{}", input);
+    }
+}
+
+#[test]
+fn test_parse_group() {
+    let sum = Unit::Exp(Box::new(Unit::Var("a".to_string())), ArithOp::Add, Box::new(Unit::Val(1.0)));
+    let test_cases = vec![
+        ("(10)", Unit::Val(10.0)),
+        ("( (\"red) )", Unit::Word("red".to_string())),
+        ("(:a + 1) * 2", Unit::Exp(Box::new(sum.clone()), ArithOp::Mul, Box::new(Unit::Val(2.0)))),
+        ("2 / (:a + 1)", Unit::Exp(Box::new(Unit::Val(2.0)), ArithOp::Div, Box::new(sum.clone()))),
+        ("(random 1 6) - (randomfloat 2)", Unit::Exp(
+            Box::new(Unit::RandomRange(Box::new(Unit::Val(1.0)), Box::new(Unit::Val(6.0)))),
+            ArithOp::Sub,
+            Box::new(Unit::RandomFloat(Box::new(Unit::Val(2.0)))),
+        )),
+    ];
+
+    for (input, expected) in test_cases {
+        let result = parse_unit(input, &Arities::new());
         match result {
             Ok((_, unit)) => assert_eq!(unit, expected),
             Err(_) => panic!("Błąd parsowania dla '{}'", input),
         }
     }
+    // name can't start with a digit
+    assert!(parse_unit("(1abc)", &Arities::new()).is_err());
 }
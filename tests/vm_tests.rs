@@ -0,0 +1,103 @@
+use std::fs;
+
+use lista7::evaluator::compile::Op;
+use lista7::evaluator::limits::Limits;
+use lista7::evaluator::value::Value;
+use lista7::parser::unit::ArithOp;
+use lista7::{interpreter, Config, Interpreter, LogoError};
+
+fn run_both(code: &str, config: Config) -> (Interpreter, Result<(), LogoError>, Interpreter, Result<(), LogoError>) {
+    let blocks = interpreter::parse(code).unwrap();
+    let mut tree = Interpreter::new(config.clone());
+    let tree_result = tree.run_blocks(blocks.clone());
+    let mut vm = Interpreter::new(config);
+    let vm_result = vm.run_compiled(&blocks);
+    (tree, tree_result, vm, vm_result)
+}
+
+fn assert_parity(code: &str, config: Config) {
+    let (tree, tree_result, vm, vm_result) = run_both(code, config);
+    assert_eq!(vm_result, tree_result, "different result for:\n{}", code);
+    assert_eq!(vm.turtle().lines, tree.turtle().lines, "different drawing for:\n{}", code);
+}
+
+#[test]
+fn test_parity_on_codes() {
+    for entry in fs::read_dir("codes").unwrap() {
+        let path = entry.unwrap().path();
+        let code = fs::read_to_string(&path).unwrap();
+        let (tree, tree_result, vm, vm_result) = run_both(&code, Config { seed: Some(7), ..Config::default() });
+
+        assert_eq!(vm_result, tree_result, "{}", path.display());
+        assert!(!tree.turtle().lines.is_empty(), "{}", path.display());
+        assert_eq!(vm.turtle().lines, tree.turtle().lines, "{}", path.display());
+    }
+}
+
+#[test]
+fn test_parity_on_errors() {
+    let programs = [
+        "fd :x",
+        "fd 10 square 5",
+        "to sq :n\n fd :n\nend\nsq",
+        "to sq :n\n fd :n\nend\nfd (sq 1)",
+        "fd random 0",
+        "repeat 3 [ fd 10 stop ] fd 100",
+        "to f :n\n repeat 4 [ if repcount == 2 [ stop ] fd :n ]\nend\nrepeat 2 [ f repcount * 10 fd # ]",
+        "to down :n\n if :n < 1 [ stop ]\n down :n - 1\nend\ndown 150",
+        "fd 10 setturtle -1 fd 10",
+        "setturtle 1.7",
+    ];
+    for program in programs {
+        assert_parity(program, Config { seed: Some(1), ..Config::default() });
+    }
+}
+
+#[test]
+fn test_parity_on_limits() {
+    let limits = Limits { max_steps: Some(500), max_lines: Some(100), ..Limits::default() };
+    assert_parity("repeat 1000 [ fd 1 rt 1 ]", Config { limits: limits.clone(), ..Config::default() });
+    assert_parity("repeat 1000 [ pu ]", Config { limits, ..Config::default() });
+}
+
+#[test]
+fn test_procedures_shared_between_runs() {
+    let mut interpreter = Interpreter::new(Config::default());
+
+    interpreter.run_compiled(&interpreter::parse("to sq :n\n repeat 4 [ fd :n rt 90 ]\nend").unwrap()).unwrap();
+    // defined by the vm, called by the tree-walking evaluator and the other way
+    interpreter.run_str("sq 10").unwrap();
+    interpreter.run_str("to tri :n\n repeat 3 [ fd :n rt 120 ]\nend").unwrap();
+    interpreter.run_compiled(&interpreter::parse("tri 10 sq 5").unwrap()).unwrap();
+
+    assert_eq!(interpreter.turtle().lines.len(), 11);
+}
+
+#[test]
+fn test_deep_recursion() {
+    let mut interpreter = Interpreter::new(Config { limits: Limits::unlimited(), ..Config::default() });
+    let program = interpreter::parse("to down :n\n if :n < 1 [ stop ]\n fd 1\n down :n - 1\n rt 1\nend\ndown 100000").unwrap();
+
+    // frames are not on the rust stack, so it doesn't overflow
+    interpreter.run_compiled(&program).unwrap();
+
+    assert_eq!(interpreter.turtle().lines.len(), 100000);
+}
+
+#[test]
+fn test_compile_expression() {
+    let interpreter = Interpreter::new(Config::default());
+    let program = interpreter.compile(&interpreter::parse("fd :size * 2").unwrap()).unwrap();
+
+    assert_eq!(program.vars, vec!["size".to_string()]);
+    assert_eq!(program.code, vec![Op::Step, Op::Load(0), Op::Push(2.0), Op::Arith(ArithOp::Mul), Op::Forward, Op::Halt]);
+}
+
+#[test]
+fn test_setturtle_bad_id() {
+    let blocks = interpreter::parse("setturtle 2 fd 10 setturtle 2.5").unwrap();
+    let mut vm = Interpreter::new(Config::default());
+    let result = vm.run_compiled(&blocks);
+    assert_eq!(result, Err(LogoError::BadInput { name: "setturtle".to_string(), value: Value::Number(2.5) }));
+    assert_eq!(vm.turtle().lines.len(), 1);
+}